@@ -0,0 +1,485 @@
+use crate::{
+    physics::FixedStepSet,
+    third_person_camera::GamepadResource,
+    virtual_joystick::{VirtualJoystickEvent, VirtualJoystickSet},
+    JoystickControllerID,
+};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    input::InputSystem,
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use serde::{Deserialize, Serialize};
+
+pub struct ActionsPlugin;
+
+impl Plugin for ActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<InputBindings>()
+            .init_asset_loader::<InputBindingsLoader>()
+            .init_resource::<InputBindings>()
+            .init_resource::<ActionState>()
+            .init_resource::<FixedActionState>()
+            .add_systems(Startup, load_input_bindings)
+            .add_systems(
+                PreUpdate,
+                (apply_loaded_input_bindings, update_action_state)
+                    .chain()
+                    .in_set(ActionSet)
+                    .after(InputSystem)
+                    .after(VirtualJoystickSet),
+            )
+            .add_systems(
+                FixedUpdate,
+                update_fixed_action_state.in_set(FixedStepSet::Prepare),
+            );
+    }
+}
+
+/// Runs in [`PreUpdate`] once all input sources have been read, so anything in [`Update`]
+/// sees this frame's [`ActionState`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionSet;
+
+/// Everything gameplay code can ask the player to do, independent of the device used
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum Action {
+    Move,
+    Jump,
+    Dash,
+    Aim,
+    ZoomIn,
+    ZoomOut,
+    ToggleCursor,
+    ToggleEditor,
+    EditorMoveMode,
+    EditorRotateMode,
+    EditorScaleMode,
+    EditorDelete,
+    EditorUndo,
+    EditorRedo,
+    EditorDuplicate,
+    EditorSave,
+}
+
+impl Action {
+    pub const ALL: [Action; 16] = [
+        Action::Move,
+        Action::Jump,
+        Action::Dash,
+        Action::Aim,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ToggleCursor,
+        Action::ToggleEditor,
+        Action::EditorMoveMode,
+        Action::EditorRotateMode,
+        Action::EditorScaleMode,
+        Action::EditorDelete,
+        Action::EditorUndo,
+        Action::EditorRedo,
+        Action::EditorDuplicate,
+        Action::EditorSave,
+    ];
+}
+
+/// Held alongside the key of an [`InputBinding::KeyChord`]. The key on either side counts
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Modifier {
+    Ctrl,
+    Shift,
+    Alt,
+}
+
+impl Modifier {
+    const ALL: [Modifier; 3] = [Modifier::Ctrl, Modifier::Shift, Modifier::Alt];
+
+    fn keys(self) -> [KeyCode; 2] {
+        match self {
+            Modifier::Ctrl => [KeyCode::ControlLeft, KeyCode::ControlRight],
+            Modifier::Shift => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            Modifier::Alt => [KeyCode::AltLeft, KeyCode::AltRight],
+        }
+    }
+}
+
+/// A single physical input that feeds an [`Action`]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputBinding {
+    Key(KeyCode),
+    /// `key` while exactly `modifiers` are held, so Ctrl+Z and Ctrl+Shift+Z can differ
+    KeyChord {
+        modifiers: Vec<Modifier>,
+        key: KeyCode,
+    },
+    Mouse(MouseButton),
+    /// A button on the gamepad selected by [`GamepadResource`]
+    GamepadButton(GamepadButtonType),
+    /// Four keys combined into a 2D axis
+    KeyAxis {
+        up: KeyCode,
+        down: KeyCode,
+        left: KeyCode,
+        right: KeyCode,
+    },
+    VirtualJoystick(JoystickControllerID),
+    /// A stick on the gamepad selected by [`GamepadResource`]. Values inside `dead_zone` are
+    /// ignored
+    GamepadStick {
+        x: GamepadAxisType,
+        y: GamepadAxisType,
+        dead_zone: f32,
+    },
+}
+
+/// The bindings used to build the [`ActionState`] each frame.
+///
+/// Loaded from `input.bindings.ron` and re-applied whenever that file changes. Can also be
+/// edited at runtime through the resource of the same type.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize, TypeUuid, TypePath)]
+#[uuid = "3c6d2d54-51a1-4c1e-9a43-7f0f6a8d2b11"]
+pub struct InputBindings {
+    pub actions: HashMap<Action, Vec<InputBinding>>,
+}
+
+impl InputBindings {
+    pub fn bindings(&self, action: Action) -> &[InputBinding] {
+        self.actions.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Adds `binding` to `action`, keeping any existing bindings
+    pub fn bind(&mut self, action: Action, binding: InputBinding) {
+        let bindings = self.actions.entry(action).or_default();
+        if !bindings.contains(&binding) {
+            bindings.push(binding);
+        }
+    }
+
+    /// Replaces every binding of `action` with `binding`
+    pub fn rebind(&mut self, action: Action, binding: InputBinding) {
+        self.actions.insert(action, vec![binding]);
+    }
+
+    pub fn unbind(&mut self, action: Action, binding: &InputBinding) {
+        if let Some(bindings) = self.actions.get_mut(&action) {
+            bindings.retain(|b| b != binding);
+        }
+    }
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        let mut bindings = Self {
+            actions: HashMap::default(),
+        };
+        bindings.bind(
+            Action::Move,
+            InputBinding::KeyAxis {
+                up: KeyCode::W,
+                down: KeyCode::S,
+                left: KeyCode::A,
+                right: KeyCode::D,
+            },
+        );
+        bindings.bind(
+            Action::Move,
+            InputBinding::KeyAxis {
+                up: KeyCode::Up,
+                down: KeyCode::Down,
+                left: KeyCode::Left,
+                right: KeyCode::Right,
+            },
+        );
+        bindings.bind(
+            Action::Move,
+            InputBinding::VirtualJoystick(JoystickControllerID::MoveJoystick),
+        );
+        bindings.bind(
+            Action::Move,
+            InputBinding::GamepadStick {
+                x: GamepadAxisType::LeftStickX,
+                y: GamepadAxisType::LeftStickY,
+                dead_zone: 0.2,
+            },
+        );
+        bindings.bind(Action::Jump, InputBinding::Key(KeyCode::Space));
+        bindings.bind(
+            Action::Jump,
+            InputBinding::GamepadButton(GamepadButtonType::South),
+        );
+        bindings.bind(Action::Dash, InputBinding::Key(KeyCode::ShiftLeft));
+        bindings.bind(
+            Action::Dash,
+            InputBinding::GamepadButton(GamepadButtonType::West),
+        );
+        bindings.bind(Action::Aim, InputBinding::Mouse(MouseButton::Right));
+        bindings.bind(
+            Action::Aim,
+            InputBinding::GamepadButton(GamepadButtonType::LeftTrigger2),
+        );
+        bindings.bind(Action::ZoomIn, InputBinding::Key(KeyCode::Equals));
+        bindings.bind(Action::ZoomOut, InputBinding::Key(KeyCode::Minus));
+        bindings.bind(Action::ToggleCursor, InputBinding::Key(KeyCode::Grave));
+        bindings.bind(Action::ToggleEditor, InputBinding::Key(KeyCode::F1));
+        bindings.bind(Action::EditorMoveMode, InputBinding::Key(KeyCode::Key1));
+        bindings.bind(Action::EditorRotateMode, InputBinding::Key(KeyCode::Key2));
+        bindings.bind(Action::EditorScaleMode, InputBinding::Key(KeyCode::Key3));
+        bindings.bind(Action::EditorDelete, InputBinding::Key(KeyCode::Delete));
+        let ctrl = |key| InputBinding::KeyChord {
+            modifiers: vec![Modifier::Ctrl],
+            key,
+        };
+        bindings.bind(Action::EditorUndo, ctrl(KeyCode::Z));
+        bindings.bind(
+            Action::EditorRedo,
+            InputBinding::KeyChord {
+                modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+                key: KeyCode::Z,
+            },
+        );
+        bindings.bind(Action::EditorRedo, ctrl(KeyCode::Y));
+        bindings.bind(Action::EditorDuplicate, ctrl(KeyCode::D));
+        bindings.bind(Action::EditorSave, ctrl(KeyCode::S));
+        bindings
+    }
+}
+
+#[derive(Default)]
+pub struct InputBindingsLoader;
+
+impl AssetLoader for InputBindingsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let bindings = ron::de::from_bytes::<InputBindings>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(bindings));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bindings.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct InputBindingsHandle(pub Handle<InputBindings>);
+
+/// Marks an on-screen button that presses `Action` while it is held
+#[derive(Component)]
+pub struct ActionButton(pub Action);
+
+#[derive(Default, Clone, Copy)]
+struct ActionData {
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+    axis: Vec2,
+    pressed_since_tick: bool,
+    released_since_tick: bool,
+}
+
+/// The state of every [`Action`] for the current frame.
+///
+/// Button actions report an axis of `Vec2::X` while pressed, and axis actions count as
+/// pressed while their axis is non-zero.
+#[derive(Resource, Default)]
+pub struct ActionState {
+    actions: HashMap<Action, ActionData>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.data(action).pressed
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.data(action).just_pressed
+    }
+
+    pub fn just_released(&self, action: Action) -> bool {
+        self.data(action).just_released
+    }
+
+    /// Combined value of every axis binding, clamped to the unit circle
+    pub fn axis(&self, action: Action) -> Vec2 {
+        self.data(action).axis
+    }
+
+    fn data(&self, action: Action) -> ActionData {
+        self.actions.get(&action).copied().unwrap_or_default()
+    }
+
+    fn update(&mut self, action: Action, pressed: bool, axis: Vec2) {
+        let data = self.actions.entry(action).or_default();
+        data.just_pressed = pressed && !data.pressed;
+        data.just_released = !pressed && data.pressed;
+        data.pressed = pressed;
+        data.axis = axis;
+        data.pressed_since_tick |= data.just_pressed;
+        data.released_since_tick |= data.just_released;
+    }
+}
+
+/// The state of every [`Action`] for the current [`FixedUpdate`] tick.
+///
+/// Presses and releases are remembered until the next tick, so they aren't lost on frames
+/// where no tick runs, and are only reported once when several ticks run in a frame.
+#[derive(Resource, Default)]
+pub struct FixedActionState(ActionState);
+
+impl std::ops::Deref for FixedActionState {
+    type Target = ActionState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+fn load_input_bindings(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(InputBindingsHandle(asset_server.load("input.bindings.ron")));
+}
+
+fn apply_loaded_input_bindings(
+    mut events: EventReader<AssetEvent<InputBindings>>,
+    handle: Option<Res<InputBindingsHandle>>,
+    assets: Res<Assets<InputBindings>>,
+    mut bindings: ResMut<InputBindings>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle: h } | AssetEvent::Modified { handle: h }
+                if *h == handle.0 =>
+            {
+                if let Some(loaded) = assets.get(h) {
+                    *bindings = loaded.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_action_state(
+    mut state: ResMut<ActionState>,
+    bindings: Res<InputBindings>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_res: Option<Res<GamepadResource>>,
+    mut virtual_joystick: EventReader<VirtualJoystickEvent<JoystickControllerID>>,
+    action_button_q: Query<(&ActionButton, &Interaction)>,
+) {
+    let mut joystick_axes: HashMap<JoystickControllerID, Vec2> = HashMap::default();
+    for joystick in virtual_joystick.iter() {
+        *joystick_axes.entry(joystick.id()).or_default() += joystick.axis();
+    }
+
+    let gamepad = gamepad_res.map(|gp| gp.0);
+
+    for action in Action::ALL {
+        let mut pressed = false;
+        let mut axis = Vec2::ZERO;
+
+        for binding in bindings.bindings(action) {
+            match binding {
+                InputBinding::Key(key) => pressed |= keys.pressed(*key),
+                InputBinding::KeyChord { modifiers, key } => {
+                    pressed |= keys.pressed(*key)
+                        && Modifier::ALL.into_iter().all(|modifier| {
+                            modifiers.contains(&modifier) == keys.any_pressed(modifier.keys())
+                        });
+                }
+                InputBinding::Mouse(button) => pressed |= mouse.pressed(*button),
+                InputBinding::GamepadButton(button_type) => {
+                    if let Some(gamepad) = gamepad {
+                        pressed |=
+                            gamepad_buttons.pressed(GamepadButton::new(gamepad, *button_type));
+                    }
+                }
+                InputBinding::KeyAxis {
+                    up,
+                    down,
+                    left,
+                    right,
+                } => {
+                    if keys.pressed(*up) {
+                        axis += Vec2::Y;
+                    }
+                    if keys.pressed(*down) {
+                        axis += Vec2::NEG_Y;
+                    }
+                    if keys.pressed(*left) {
+                        axis += Vec2::NEG_X;
+                    }
+                    if keys.pressed(*right) {
+                        axis += Vec2::X;
+                    }
+                }
+                InputBinding::VirtualJoystick(id) => {
+                    axis += joystick_axes.get(id).copied().unwrap_or_default();
+                }
+                InputBinding::GamepadStick { x, y, dead_zone } => {
+                    let Some(gamepad) = gamepad else {
+                        continue;
+                    };
+                    let stick = Vec2::new(
+                        gamepad_axes
+                            .get(GamepadAxis::new(gamepad, *x))
+                            .unwrap_or_default(),
+                        gamepad_axes
+                            .get(GamepadAxis::new(gamepad, *y))
+                            .unwrap_or_default(),
+                    );
+                    if stick.length() > *dead_zone {
+                        axis += stick;
+                    }
+                }
+            }
+        }
+
+        for (button, interaction) in action_button_q.iter() {
+            if button.0 == action && *interaction == Interaction::Pressed {
+                pressed = true;
+            }
+        }
+
+        axis = axis.clamp_length_max(1.0);
+        if axis != Vec2::ZERO {
+            pressed = true;
+        } else if pressed {
+            axis = Vec2::X;
+        }
+
+        state.update(action, pressed, axis);
+    }
+}
+
+fn update_fixed_action_state(mut state: ResMut<ActionState>, mut fixed: ResMut<FixedActionState>) {
+    for (action, data) in state.actions.iter_mut() {
+        fixed.0.actions.insert(
+            *action,
+            ActionData {
+                // a tap shorter than a tick still counts as held for that tick
+                pressed: data.pressed || data.pressed_since_tick,
+                just_pressed: data.pressed_since_tick,
+                just_released: data.released_since_tick,
+                axis: data.axis,
+                ..default()
+            },
+        );
+        data.pressed_since_tick = false;
+        data.released_since_tick = false;
+    }
+}
@@ -1,5 +1,16 @@
-use crate::{third_person_camera::*, virtual_joystick::VirtualJoystickEvent, JoystickControllerID};
-use bevy::{core_pipeline::bloom::BloomSettings, prelude::*};
+use crate::{
+    actions::{Action, ActionState},
+    checkpoint::{PlayerRespawned, RespawnSet},
+    third_person_camera::*,
+    virtual_joystick::VirtualJoystickEvent,
+    JoystickControllerID,
+};
+use bevy::{
+    core_pipeline::bloom::BloomSettings,
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+use bevy_rapier3d::prelude::CollisionGroups;
 
 pub struct CameraPlugin;
 
@@ -8,8 +19,12 @@ impl Plugin for CameraPlugin {
         app.add_systems(Startup, spawn_camera).add_systems(
             Update,
             (
-                toggle_zoom_on_cursor_lock,
+                toggle_cursor_lock,
+                zoom_on_actions,
                 disable_camera_orbit_while_joystick_held,
+                snap_camera_behind_respawned_player
+                    .after(RespawnSet)
+                    .before(ThirdPersonCameraSyncSet),
             ),
         );
     }
@@ -19,10 +34,11 @@ impl Plugin for CameraPlugin {
 pub struct MainCamera;
 
 pub fn spawn_camera(mut commands: Commands) {
+    let transform = Transform::from_xyz(16.0, 16.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y);
     commands.spawn((
         Name::new("Camera"),
         Camera3dBundle {
-            transform: Transform::from_xyz(16.0, 16.0, 16.0).looking_at(Vec3::ZERO, Vec3::Y),
+            transform,
             camera: Camera {
                 hdr: true,
                 ..default()
@@ -32,7 +48,11 @@ pub fn spawn_camera(mut commands: Commands) {
         MainCamera,
         ThirdPersonCamera {
             zoom: Zoom::new(4.0, 16.0),
-            cursor_lock_key: KeyCode::Grave,
+            orbit: Orbit::from_rotation(transform.rotation),
+            collision: CameraCollision::new(0.2, CollisionGroups::default()),
+            smoothing: CameraSmoothing::new(0.08, 0.2, 0.05, 0.15),
+            // cursor locking is driven by `Action::ToggleCursor` instead
+            cursor_lock_toggle_enabled: false,
             ..default()
         },
         BloomSettings {
@@ -42,14 +62,47 @@ pub fn spawn_camera(mut commands: Commands) {
     ));
 }
 
-pub fn toggle_zoom_on_cursor_lock(
-    input: Res<Input<KeyCode>>,
+pub fn toggle_cursor_lock(
+    actions: Res<ActionState>,
     mut camera_query: Query<&mut ThirdPersonCamera, With<MainCamera>>,
+    mut window_query: Query<&mut Window, With<PrimaryWindow>>,
 ) {
     let mut camera = camera_query.get_single_mut().unwrap();
-    if input.just_pressed(camera.cursor_lock_key) {
+    if actions.just_pressed(Action::ToggleCursor) {
+        camera.cursor_lock_active = !camera.cursor_lock_active;
         camera.zoom_enabled = !camera.zoom_enabled;
     }
+
+    let mut window = window_query.get_single_mut().unwrap();
+    window.cursor.grab_mode = if camera.cursor_lock_active {
+        CursorGrabMode::Locked
+    } else {
+        CursorGrabMode::None
+    };
+    window.cursor.visible = !camera.cursor_lock_active;
+}
+
+/// Fraction of the zoom radius covered per second while a zoom action is held
+const ZOOM_SPEED: f32 = 0.6;
+
+pub fn zoom_on_actions(
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    mut camera_query: Query<&mut ThirdPersonCamera, With<MainCamera>>,
+) {
+    let mut camera = camera_query.get_single_mut().unwrap();
+    if !camera.zoom_enabled {
+        return;
+    }
+
+    let step = camera.zoom.radius * ZOOM_SPEED * time.delta_seconds();
+    let mut new_radius = camera.zoom.radius;
+    if actions.pressed(Action::ZoomOut) {
+        new_radius += step;
+    } else if actions.pressed(Action::ZoomIn) {
+        new_radius -= step;
+    }
+    camera.zoom.radius = new_radius.clamp(camera.zoom.min, camera.zoom.max);
 }
 
 pub fn disable_camera_orbit_while_joystick_held(
@@ -57,13 +110,31 @@ pub fn disable_camera_orbit_while_joystick_held(
     mut camera_query: Query<&mut ThirdPersonCamera, With<MainCamera>>,
 ) {
     let mut camera = camera_query.get_single_mut().unwrap();
-    for joystick in virtual_joystick.iter() {
-        match joystick.id() {
-            JoystickControllerID::MoveJoystick => {
-                camera.orbit_enabled = false;
-                return;
+    camera.orbit_enabled = virtual_joystick
+        .iter()
+        .filter(|joystick| joystick.id() == JoystickControllerID::MoveJoystick)
+        .count()
+        == 0;
+}
+
+/// Turns the cameras following a player that just respawned to look over their shoulder, keeping
+/// their pitch. Skips the camera smoothing, which would otherwise sweep them across the level
+pub fn snap_camera_behind_respawned_player(
+    mut respawned_reader: EventReader<PlayerRespawned>,
+    player_query: Query<&Transform, With<ThirdPersonCameraTarget>>,
+    target_query: Query<Entity, With<ThirdPersonCameraTarget>>,
+    mut camera_query: Query<&mut ThirdPersonCamera>,
+) {
+    for respawned in respawned_reader.iter() {
+        let Ok(player_transform) = player_query.get(respawned.player) else {
+            continue;
+        };
+        for mut camera in camera_query.iter_mut() {
+            if camera.resolve_target(&target_query) != Some(respawned.player) {
+                continue;
             }
+            camera.orbit.yaw = Orbit::from_rotation(player_transform.rotation).yaw;
+            camera.snap();
         }
     }
-    camera.orbit_enabled = true;
 }
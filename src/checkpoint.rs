@@ -0,0 +1,213 @@
+use crate::goal::RunSet;
+use crate::physics::FixedStepSet;
+use crate::player::{CharacterMotorState, DashState, JumpState, Player};
+use crate::trigger::{TriggerContents, TriggerEntered, TriggerSet};
+use crate::ui::ScreenFade;
+use bevy::prelude::*;
+
+pub struct CheckpointPlugin;
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<KillPlane>()
+            .add_event::<CheckpointReached>()
+            .add_event::<PlayerDied>()
+            .add_event::<PlayerRespawned>()
+            .add_systems(
+                FixedUpdate,
+                (reach_checkpoints, kill_players)
+                    .chain()
+                    .after(TriggerSet)
+                    .in_set(RunSet)
+                    .in_set(FixedStepSet::Record),
+            )
+            .add_systems(
+                Update,
+                (start_respawn, respawn_players).chain().in_set(RespawnSet),
+            );
+    }
+}
+
+/// Systems that react to [`PlayerRespawned`] in the same frame should run after this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RespawnSet;
+
+/// [`TriggerVolume`](crate::trigger::TriggerVolume) that becomes the player's [`RespawnPoint`]
+/// once entered
+#[derive(Component)]
+pub struct Checkpoint {
+    /// Where the player reappears, relative to the checkpoint
+    pub respawn_offset: Vec3,
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self {
+            respawn_offset: Vec3::Y,
+        }
+    }
+}
+
+/// [`TriggerVolume`](crate::trigger::TriggerVolume) that kills the player while inside it
+#[derive(Component)]
+pub struct KillVolume;
+
+/// Players below this height die
+#[derive(Resource)]
+pub struct KillPlane {
+    pub height: f32,
+}
+
+impl Default for KillPlane {
+    fn default() -> Self {
+        Self { height: -20.0 }
+    }
+}
+
+/// Where a player comes back after dying
+#[derive(Component, Clone, Copy)]
+pub struct RespawnPoint {
+    pub transform: Transform,
+    /// The checkpoint that set this point, if any
+    pub checkpoint: Option<Entity>,
+}
+
+impl RespawnPoint {
+    pub fn from_transform(transform: Transform) -> Self {
+        Self {
+            transform,
+            checkpoint: None,
+        }
+    }
+}
+
+/// Present on a player between dying and respawning
+#[derive(Component)]
+pub struct Respawning {
+    timer: Timer,
+}
+
+#[derive(Event)]
+pub struct CheckpointReached {
+    pub player: Entity,
+    pub checkpoint: Entity,
+}
+
+#[derive(Event)]
+pub struct PlayerDied {
+    pub player: Entity,
+    pub position: Vec3,
+}
+
+#[derive(Event)]
+pub struct PlayerRespawned {
+    pub player: Entity,
+}
+
+pub fn reach_checkpoints(
+    mut entered_reader: EventReader<TriggerEntered>,
+    mut player_query: Query<&mut RespawnPoint, With<Player>>,
+    checkpoint_query: Query<(&Checkpoint, &GlobalTransform)>,
+    mut reached_writer: EventWriter<CheckpointReached>,
+) {
+    for entered in entered_reader.iter() {
+        let Ok((checkpoint, checkpoint_transform)) = checkpoint_query.get(entered.trigger) else {
+            continue;
+        };
+        let Ok(mut respawn_point) = player_query.get_mut(entered.entity) else {
+            continue;
+        };
+        if respawn_point.checkpoint == Some(entered.trigger) {
+            continue;
+        }
+        let (_, rotation, translation) = checkpoint_transform.to_scale_rotation_translation();
+        *respawn_point = RespawnPoint {
+            transform: Transform::from_translation(translation + checkpoint.respawn_offset)
+                .with_rotation(rotation),
+            checkpoint: Some(entered.trigger),
+        };
+        reached_writer.send(CheckpointReached {
+            player: entered.entity,
+            checkpoint: entered.trigger,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn kill_players(
+    player_query: Query<(Entity, &Transform), (With<Player>, Without<Respawning>)>,
+    kill_volume_query: Query<&TriggerContents, With<KillVolume>>,
+    kill_plane: Res<KillPlane>,
+    mut died_writer: EventWriter<PlayerDied>,
+) {
+    for (player, transform) in player_query.iter() {
+        // what's inside rather than entries, so a player that respawns inside a volume, or
+        // enters it while respawning, still dies
+        let in_kill_volume = kill_volume_query
+            .iter()
+            .any(|contents| contents.contains(player));
+        if transform.translation.y < kill_plane.height || in_kill_volume {
+            died_writer.send(PlayerDied {
+                player,
+                position: transform.translation,
+            });
+        }
+    }
+}
+
+fn start_respawn(
+    mut commands: Commands,
+    mut died_reader: EventReader<PlayerDied>,
+    respawning_query: Query<(), With<Respawning>>,
+    mut fade: ResMut<ScreenFade>,
+) {
+    for died in died_reader.iter() {
+        // a player can die again on the ticks before the respawn starts
+        if respawning_query.contains(died.player) {
+            continue;
+        }
+        fade.target = 1.0;
+        commands.entity(died.player).insert(Respawning {
+            timer: Timer::from_seconds(fade.duration, TimerMode::Once),
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn respawn_players(
+    mut commands: Commands,
+    mut player_query: Query<(
+        Entity,
+        &mut Respawning,
+        &RespawnPoint,
+        &mut Transform,
+        &mut CharacterMotorState,
+        Option<&mut DashState>,
+        Option<&mut JumpState>,
+    )>,
+    mut fade: ResMut<ScreenFade>,
+    mut respawned_writer: EventWriter<PlayerRespawned>,
+    time: Res<Time>,
+) {
+    for (player, mut respawning, respawn_point, mut transform, mut motor, dash, jump) in
+        player_query.iter_mut()
+    {
+        // wait for the screen to be black before moving the player
+        if !respawning.timer.tick(time.delta()).finished() {
+            continue;
+        }
+
+        *transform = respawn_point.transform;
+        *motor = CharacterMotorState::default();
+        if let Some(mut dash) = dash {
+            *dash = DashState::default();
+        }
+        if let Some(mut jump) = jump {
+            *jump = JumpState::default();
+        }
+
+        commands.entity(player).remove::<Respawning>();
+        fade.target = 0.0;
+        respawned_writer.send(PlayerRespawned { player });
+    }
+}
@@ -0,0 +1,389 @@
+use crate::checkpoint::Checkpoint;
+use crate::goal::Goal;
+use crate::player::{CharacterMotor, Dash, Grounded, Jump, MoveSpeed, Player, PLAYER_TAG};
+use crate::trigger::TriggerVolume;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+
+/// Platforms kept ahead of the furthest one reached on an endless course
+const ENDLESS_LOOKAHEAD: u32 = 12;
+/// Fraction of the computed reach that generated gaps may use
+const REACH_SAFETY_MARGIN: f32 = 0.8;
+
+pub struct CoursePlugin;
+
+impl Plugin for CoursePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (start_courses, extend_courses).chain());
+    }
+}
+
+/// How far a body can travel in a single jump, derived from its movement tuning
+#[derive(Clone, Copy, Debug)]
+pub struct JumpReach {
+    /// Upward speed on takeoff
+    pub jump_speed: f32,
+    pub gravity: f32,
+    pub move_speed: f32,
+    /// Extra horizontal distance from a dash in the air
+    pub dash_distance: f32,
+}
+
+impl JumpReach {
+    pub fn new(
+        jump: &Jump,
+        motor: &CharacterMotor,
+        move_speed: &MoveSpeed,
+        dash: Option<&Dash>,
+    ) -> Self {
+        let can_air_dash = dash
+            .is_some_and(|dash| dash.max_charges > 0 && !matches!(dash.max_air_dashes, Some(0)));
+        Self {
+            jump_speed: jump.jump_power,
+            gravity: motor.gravity,
+            move_speed: move_speed.0,
+            dash_distance: match dash {
+                Some(dash) if can_air_dash => dash.speed * dash.duration,
+                _ => 0.0,
+            },
+        }
+    }
+
+    /// Checks the reach describes an actual jump, since anything else makes the distances below
+    /// meaningless
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+        if !positive(self.gravity) {
+            return Err(format!("gravity must be positive, not {}", self.gravity));
+        }
+        if !positive(self.jump_speed) {
+            return Err(format!(
+                "jump speed must be positive, not {}",
+                self.jump_speed
+            ));
+        }
+        if !self.move_speed.is_finite() || self.move_speed < 0.0 {
+            return Err(format!("move speed can't be {}", self.move_speed));
+        }
+        if !self.dash_distance.is_finite() || self.dash_distance < 0.0 {
+            return Err(format!("dash distance can't be {}", self.dash_distance));
+        }
+        Ok(())
+    }
+
+    /// Highest point of a jump above the takeoff height
+    pub fn max_height(&self) -> f32 {
+        self.jump_speed * self.jump_speed / (2.0 * self.gravity)
+    }
+
+    /// Longest horizontal distance to a landing `rise` units above the takeoff height, or
+    /// `None` if it is too high
+    pub fn max_distance(&self, rise: f32) -> Option<f32> {
+        let discriminant = self.jump_speed * self.jump_speed - 2.0 * self.gravity * rise;
+        if discriminant < 0.0 {
+            return None;
+        }
+        // time until the body comes back down to the landing height
+        let air_time = (self.jump_speed + discriminant.sqrt()) / self.gravity;
+        Some(self.move_speed * air_time + self.dash_distance)
+    }
+
+    pub fn can_reach(&self, gap: f32, rise: f32) -> bool {
+        self.max_distance(rise)
+            .is_some_and(|distance| gap <= distance)
+    }
+}
+
+/// Position and size of a generated platform, relative to the course
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PlatformLayout {
+    pub center: Vec3,
+    pub size: Vec3,
+}
+
+impl PlatformLayout {
+    /// Half of the platform's extent along a horizontal `direction`
+    fn half_extent_along(&self, direction: Vec3) -> f32 {
+        (direction.x.abs() * self.size.x + direction.z.abs() * self.size.z) / 2.0
+    }
+}
+
+/// Lays out platforms one after the other, heading towards -Z.
+///
+/// The same seed and [`JumpReach`] always give the same course. Every gap is checked against
+/// the reach and shortened when it couldn't be cleared.
+pub struct CourseGenerator {
+    rng: ChaCha8Rng,
+    reach: JumpReach,
+    previous: Option<PlatformLayout>,
+}
+
+impl CourseGenerator {
+    /// `reach` should pass [`JumpReach::validate`]
+    pub fn new(seed: u64, reach: JumpReach) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            reach,
+            previous: None,
+        }
+    }
+
+    pub fn next_platform(&mut self) -> PlatformLayout {
+        let Some(previous) = self.previous else {
+            let start = PlatformLayout {
+                center: Vec3::new(0.0, -0.5, 0.0),
+                size: Vec3::new(4.0, 1.0, 4.0),
+            };
+            self.previous = Some(start);
+            return start;
+        };
+
+        // always draw the same numbers in the same order, so clamping below never shifts the
+        // rest of the course
+        let size = Vec3::new(
+            self.rng.gen_range(2.0..5.0),
+            1.0,
+            self.rng.gen_range(2.0..5.0),
+        );
+        let sampled_rise = self.rng.gen_range(-3.0..3.0);
+        let sampled_gap = self.rng.gen_range(1.0..8.0);
+        let sideways = self.rng.gen_range(-0.75..0.75);
+
+        let rise = f32::min(sampled_rise, self.reach.max_height() * REACH_SAFETY_MARGIN);
+        let max_gap = self.reach.max_distance(rise).unwrap_or(0.0) * REACH_SAFETY_MARGIN;
+        let gap = f32::min(sampled_gap, max_gap);
+
+        let direction = Vec3::new(sideways, 0.0, -1.0).normalize();
+        let next = PlatformLayout {
+            center: Vec3::new(0.0, previous.center.y + rise, 0.0),
+            size,
+        };
+        let distance =
+            previous.half_extent_along(direction) + gap + next.half_extent_along(direction);
+        let next = PlatformLayout {
+            center: next.center
+                + Vec3::new(previous.center.x, 0.0, previous.center.z)
+                + direction * distance,
+            ..next
+        };
+        debug_assert!(self.reach.can_reach(gap, rise));
+
+        self.previous = Some(next);
+        next
+    }
+}
+
+/// Procedurally generated platforms, spawned as children of this entity
+#[derive(Component)]
+pub struct Course {
+    pub seed: u64,
+    /// Number of platforms, or `None` to keep generating ahead of the player
+    pub length: Option<u32>,
+    /// A checkpoint is placed on every n-th platform. `0` for none
+    pub checkpoint_every: u32,
+    /// Whether the last platform of a course with a `length` is a [`Goal`]
+    pub goal: bool,
+    pub material: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+struct CourseState {
+    generator: CourseGenerator,
+    spawned: u32,
+    reached: u32,
+}
+
+/// A platform of a [`Course`]
+#[derive(Component)]
+pub struct CoursePlatform {
+    pub course: Entity,
+    pub index: u32,
+}
+
+fn start_courses(
+    mut commands: Commands,
+    course_query: Query<(Entity, &Course), Without<CourseState>>,
+    player_query: Query<(&Jump, &CharacterMotor, &MoveSpeed, Option<&Dash>), With<Player>>,
+    mut warned: Local<bool>,
+) {
+    // courses are laid out for the player's movement, so wait for it to exist
+    let Ok((jump, motor, move_speed, dash)) = player_query.get_single() else {
+        return;
+    };
+    let reach = JumpReach::new(jump, motor, move_speed, dash);
+    // waits for usable stats rather than laying out a course that can't be finished
+    if let Err(error) = reach.validate() {
+        if !course_query.is_empty() && !*warned {
+            warn!("Not generating courses for the player's stats: {error}");
+            *warned = true;
+        }
+        return;
+    }
+    *warned = false;
+
+    for (entity, course) in course_query.iter() {
+        commands.entity(entity).insert(CourseState {
+            generator: CourseGenerator::new(course.seed, reach),
+            spawned: 0,
+            reached: 0,
+        });
+    }
+}
+
+fn extend_courses(
+    mut commands: Commands,
+    mut course_query: Query<(Entity, &Course, &mut CourseState)>,
+    platform_query: Query<(Entity, &CoursePlatform)>,
+    grounded_query: Query<&Grounded, With<Player>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    for (course_entity, course, mut state) in course_query.iter_mut() {
+        for grounded in grounded_query.iter() {
+            if let Ok((_, platform)) = platform_query.get(grounded.entity) {
+                if platform.course == course_entity {
+                    state.reached = state.reached.max(platform.index);
+                }
+            }
+        }
+
+        let target = course.length.unwrap_or(state.reached + ENDLESS_LOOKAHEAD);
+        while state.spawned < target {
+            let index = state.spawned;
+            let layout = state.generator.next_platform();
+            let has_checkpoint =
+                course.checkpoint_every > 0 && index > 0 && index % course.checkpoint_every == 0;
+            let has_goal = course.goal && course.length == Some(index + 1);
+
+            commands.entity(course_entity).with_children(|parent| {
+                let mut platform = parent.spawn((
+                    Name::new(format!("Course Platform {index}")),
+                    PbrBundle {
+                        transform: Transform::from_translation(layout.center),
+                        mesh: meshes.add(Mesh::from(shape::Box::new(
+                            layout.size.x,
+                            layout.size.y,
+                            layout.size.z,
+                        ))),
+                        material: course.material.clone(),
+                        ..default()
+                    },
+                    Collider::cuboid(
+                        layout.size.x / 2.0,
+                        layout.size.y / 2.0,
+                        layout.size.z / 2.0,
+                    ),
+                    RigidBody::Fixed,
+                    CoursePlatform {
+                        course: course_entity,
+                        index,
+                    },
+                ));
+                if has_checkpoint {
+                    platform.with_children(|platform| {
+                        platform.spawn((
+                            Name::new("Checkpoint"),
+                            TransformBundle::from_transform(Transform::from_xyz(
+                                0.0,
+                                layout.size.y / 2.0,
+                                0.0,
+                            )),
+                            Collider::cuboid(layout.size.x / 2.0, 1.0, layout.size.z / 2.0),
+                            Sensor,
+                            TriggerVolume::tagged(PLAYER_TAG),
+                            Checkpoint::default(),
+                        ));
+                    });
+                }
+                if has_goal {
+                    platform.with_children(|platform| {
+                        platform.spawn((
+                            Name::new("Goal"),
+                            TransformBundle::from_transform(Transform::from_xyz(
+                                0.0,
+                                layout.size.y / 2.0,
+                                0.0,
+                            )),
+                            Collider::cuboid(layout.size.x / 2.0, 1.0, layout.size.z / 2.0),
+                            Sensor,
+                            TriggerVolume::tagged(PLAYER_TAG),
+                            Goal,
+                        ));
+                    });
+                }
+            });
+            state.spawned += 1;
+        }
+
+        // endless courses forget platforms the player can't get back to, but keep everything
+        // since the last checkpoint
+        if course.length.is_none() {
+            let keep_behind = course.checkpoint_every.max(1) * 2;
+            for (platform_entity, platform) in platform_query.iter() {
+                if platform.course == course_entity && platform.index + keep_behind < state.reached
+                {
+                    commands.entity(platform_entity).despawn_recursive();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reach() -> JumpReach {
+        JumpReach {
+            jump_speed: 32.0,
+            gravity: 8.0 * 9.81,
+            move_speed: 6.0,
+            dash_distance: 4.8,
+        }
+    }
+
+    fn layouts(seed: u64, count: usize) -> Vec<PlatformLayout> {
+        let mut generator = CourseGenerator::new(seed, reach());
+        (0..count).map(|_| generator.next_platform()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_course() {
+        assert_eq!(layouts(7, 50), layouts(7, 50));
+        assert_ne!(layouts(7, 50), layouts(8, 50));
+    }
+
+    #[test]
+    fn every_gap_is_reachable() {
+        let reach = reach();
+        for pair in layouts(3, 200).windows(2) {
+            let (from, to) = (pair[0], pair[1]);
+            let rise = to.center.y - from.center.y;
+            let offset = Vec3::new(
+                to.center.x - from.center.x,
+                0.0,
+                to.center.z - from.center.z,
+            );
+            let direction = offset.normalize();
+            let gap = offset.length()
+                - from.half_extent_along(direction)
+                - to.half_extent_along(direction);
+            assert!(reach.can_reach(gap, rise), "{from:?} to {to:?}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_impossible_jumps() {
+        assert!(reach().validate().is_ok());
+        for gravity in [0.0, -9.81, f32::NAN] {
+            let reach = JumpReach { gravity, ..reach() };
+            assert!(reach.validate().is_err(), "gravity {gravity}");
+        }
+        let reach = JumpReach {
+            jump_speed: 0.0,
+            ..reach()
+        };
+        assert!(reach.validate().is_err());
+    }
+}
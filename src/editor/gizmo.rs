@@ -0,0 +1,414 @@
+use super::{editor_active, EditorSet, LevelEditor};
+use crate::camera::MainCamera;
+use crate::level::{LevelItem, LevelMarker};
+use bevy::{prelude::*, render::primitives::Aabb, window::PrimaryWindow};
+use bevy_rapier3d::prelude::*;
+
+/// Length of the handles, relative to their distance from the camera
+const HANDLE_SCALE: f32 = 0.15;
+/// How close to a handle a click has to be, relative to the handle's length
+const HANDLE_PICK_RADIUS: f32 = 0.12;
+/// Radians turned per pixel dragged across the screen
+const ROTATE_SPEED: f32 = 0.01;
+/// Snapping steps used while Ctrl is held
+const MOVE_SNAP: f32 = 0.5;
+const ROTATE_SNAP: f32 = std::f32::consts::PI / 12.0;
+const SCALE_SNAP: f32 = 0.25;
+/// Furthest a click can select something
+const MAX_PICK_DISTANCE: f32 = 1000.0;
+
+const AXIS_COLORS: [Color; 3] = [Color::RED, Color::GREEN, Color::BLUE];
+const ACTIVE_HANDLE_COLOR: Color = Color::YELLOW;
+const SELECTION_COLOR: Color = Color::WHITE;
+
+pub struct GizmoPlugin;
+
+impl Plugin for GizmoPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GizmoDrag>().add_systems(
+            Update,
+            (
+                (drag_handles, select_clicked_items).chain(),
+                (draw_markers, draw_selection),
+            )
+                .chain()
+                .after(EditorSet)
+                .run_if(editor_active),
+        );
+    }
+}
+
+/// What dragging a handle of the selection does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GizmoMode {
+    /// Along the world axes
+    #[default]
+    Move,
+    /// Around the world axes
+    Rotate,
+    /// Along the selection's own axes
+    Scale,
+}
+
+/// The handle being dragged, if any
+#[derive(Resource, Default)]
+struct GizmoDrag(Option<Drag>);
+
+struct Drag {
+    entity: Entity,
+    axis: usize,
+    /// The selected entry's saved transform when the drag started
+    start: Transform,
+    /// Where the drag has moved it so far
+    current: Transform,
+    /// Where along the axis, or for rotation where on screen, the handle was grabbed
+    grab: f32,
+}
+
+/// Where the handles of `transform` are and how long they are, seen from `camera`
+fn handles(mode: GizmoMode, transform: &Transform, camera: Vec3) -> ([Vec3; 3], f32) {
+    let axes = match mode {
+        GizmoMode::Move | GizmoMode::Rotate => [Vec3::X, Vec3::Y, Vec3::Z],
+        GizmoMode::Scale => [
+            transform.rotation * Vec3::X,
+            transform.rotation * Vec3::Y,
+            transform.rotation * Vec3::Z,
+        ],
+    };
+    let length = transform.translation.distance(camera) * HANDLE_SCALE;
+    (axes, length)
+}
+
+/// How far along `axis` from `origin` the point closest to `ray` is, and how far the ray passes
+/// from that point
+fn closest_on_axis(ray: Ray, origin: Vec3, axis: Vec3) -> Option<(f32, f32)> {
+    let offset = ray.origin - origin;
+    let cos = ray.direction.dot(axis);
+    let denominator = 1.0 - cos * cos;
+    // looking straight down the axis
+    if denominator < 1e-4 {
+        return None;
+    }
+    let along_ray = (cos * axis.dot(offset) - ray.direction.dot(offset)) / denominator;
+    let along_axis = (axis.dot(offset) - cos * ray.direction.dot(offset)) / denominator;
+    let distance = ray
+        .get_point(along_ray)
+        .distance(origin + axis * along_axis);
+    Some((along_axis, distance))
+}
+
+/// Where `ray` crosses the plane through `origin` facing `normal`
+fn intersect_plane(ray: Ray, origin: Vec3, normal: Vec3) -> Option<Vec3> {
+    let distance = ray.intersect_plane(origin, normal)?;
+    Some(ray.get_point(distance))
+}
+
+fn snap(value: f32, step: f32, snapping: bool) -> f32 {
+    if snapping {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+fn cursor_ray(
+    window_query: &Query<&Window, With<PrimaryWindow>>,
+    camera_query: &Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) -> Option<Ray> {
+    let cursor = window_query.get_single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = camera_query.get_single().ok()?;
+    camera.viewport_to_world(camera_transform, cursor)
+}
+
+fn selected_entity(
+    editor: &LevelEditor,
+    item_query: &Query<(Entity, &LevelItem)>,
+) -> Option<Entity> {
+    let selected = editor.selected?;
+    item_query
+        .iter()
+        .find_map(|(entity, item)| (*item == selected).then_some(entity))
+}
+
+/// The handle of the selection under the cursor, and where on it
+fn grabbed_handle(mode: GizmoMode, ray: Ray, transform: &Transform) -> Option<(usize, f32)> {
+    let (axes, length) = handles(mode, transform, ray.origin);
+    let origin = transform.translation;
+    let mut closest = None;
+    for (index, axis) in axes.into_iter().enumerate() {
+        let (grab, distance) = match mode {
+            GizmoMode::Move | GizmoMode::Scale => {
+                let Some((along, distance)) = closest_on_axis(ray, origin, axis) else {
+                    continue;
+                };
+                if !(0.0..=length).contains(&along) {
+                    continue;
+                }
+                (along, distance)
+            }
+            GizmoMode::Rotate => {
+                let Some(point) = intersect_plane(ray, origin, axis) else {
+                    continue;
+                };
+                (0.0, (point.distance(origin) - length).abs())
+            }
+        };
+        if distance < length * HANDLE_PICK_RADIUS
+            && closest.is_none_or(|(_, _, closest)| distance < closest)
+        {
+            closest = Some((index, grab, distance));
+        }
+    }
+    closest.map(|(index, grab, _)| (index, grab))
+}
+
+#[allow(clippy::too_many_arguments)]
+fn drag_handles(
+    mut drag: ResMut<GizmoDrag>,
+    mut editor: ResMut<LevelEditor>,
+    mouse: Res<Input<MouseButton>>,
+    keys: Res<Input<KeyCode>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    item_query: Query<(Entity, &LevelItem)>,
+    mut transform_query: Query<&mut Transform>,
+) {
+    let Some(ray) = cursor_ray(&window_query, &camera_query) else {
+        return;
+    };
+
+    if mouse.just_pressed(MouseButton::Left) {
+        drag.0 = selected_entity(&editor, &item_query).and_then(|entity| {
+            // starts from the saved transform, not the entity's, which a moving platform animates
+            let start = editor.selected_transform()?;
+            let (axis, grab) = grabbed_handle(editor.mode, ray, &start)?;
+            let grab = match editor.mode {
+                GizmoMode::Move | GizmoMode::Scale => grab,
+                GizmoMode::Rotate => window_query.get_single().ok()?.cursor_position()?.x,
+            };
+            Some(Drag {
+                entity,
+                axis,
+                start,
+                current: start,
+                grab,
+            })
+        });
+        return;
+    }
+    let Some(current) = &mut drag.0 else {
+        return;
+    };
+    let Ok(mut preview) = transform_query.get_mut(current.entity) else {
+        drag.0 = None;
+        return;
+    };
+
+    if mouse.pressed(MouseButton::Left) {
+        let snapping = keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+        let start = current.start;
+        let mut transform = current.current;
+        let (axes, _) = handles(editor.mode, &start, ray.origin);
+        let axis = axes[current.axis];
+        match editor.mode {
+            GizmoMode::Move => {
+                if let Some((along, _)) = closest_on_axis(ray, start.translation, axis) {
+                    let moved = snap(along - current.grab, MOVE_SNAP, snapping);
+                    transform.translation = start.translation + axis * moved;
+                }
+            }
+            GizmoMode::Rotate => {
+                let Some(cursor) = window_query
+                    .get_single()
+                    .ok()
+                    .and_then(Window::cursor_position)
+                else {
+                    return;
+                };
+                let angle = snap(
+                    (cursor.x - current.grab) * ROTATE_SPEED,
+                    ROTATE_SNAP,
+                    snapping,
+                );
+                transform.rotation = Quat::from_axis_angle(axis, angle) * start.rotation;
+            }
+            GizmoMode::Scale => {
+                if let Some((along, _)) = closest_on_axis(ray, start.translation, axis) {
+                    let factor = along / current.grab.max(f32::EPSILON);
+                    let scale = snap(start.scale[current.axis] * factor, SCALE_SNAP, snapping);
+                    transform.scale[current.axis] = scale.max(SCALE_SNAP / 4.0);
+                }
+            }
+        }
+        current.current = transform;
+        *preview = transform;
+    } else {
+        // the whole drag is a single edit, written back to the level
+        if current.current != current.start {
+            let transform = current.current;
+            editor.set_selected_transform(transform);
+        }
+        drag.0 = None;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn select_clicked_items(
+    drag: Res<GizmoDrag>,
+    mut editor: ResMut<LevelEditor>,
+    mouse: Res<Input<MouseButton>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    interaction_query: Query<&Interaction>,
+    item_query: Query<&LevelItem>,
+    parent_query: Query<&Parent>,
+    rapier_ctx: Res<RapierContext>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || drag.0.is_some() {
+        return;
+    }
+    // clicks on the palette are not meant for the level behind it
+    if interaction_query
+        .iter()
+        .any(|interaction| *interaction != Interaction::None)
+    {
+        return;
+    }
+    let Some(ray) = cursor_ray(&window_query, &camera_query) else {
+        return;
+    };
+
+    let mut closest: Option<(LevelItem, f32)> = None;
+    rapier_ctx.intersections_with_ray(
+        ray.origin,
+        ray.direction,
+        MAX_PICK_DISTANCE,
+        true,
+        QueryFilter::new(),
+        |entity, intersection| {
+            // volumes the camera is inside of would hide everything else
+            if intersection.toi <= 0.0 || closest.is_some_and(|(_, toi)| toi < intersection.toi) {
+                return true;
+            }
+            // colliders may sit anywhere below what the level spawned
+            if let Some(item) = std::iter::once(entity)
+                .chain(parent_query.iter_ancestors(entity))
+                .find_map(|entity| item_query.get(entity).ok())
+            {
+                closest = Some((*item, intersection.toi));
+            }
+            true
+        },
+    );
+    editor.selected = closest.map(|(item, _)| item);
+}
+
+/// Shows the otherwise invisible gameplay volumes
+fn draw_markers(
+    mut gizmos: Gizmos,
+    editor: Res<LevelEditor>,
+    item_query: Query<(&LevelItem, &GlobalTransform)>,
+) {
+    let Some(level) = editor.level() else {
+        return;
+    };
+    for (item, transform) in item_query.iter() {
+        let LevelItem::Marker(index) = *item else {
+            continue;
+        };
+        let Some(marker) = level.markers.get(index) else {
+            continue;
+        };
+        let color = match marker {
+            LevelMarker::Checkpoint { .. } => Color::CYAN,
+            LevelMarker::KillVolume { .. } => Color::ORANGE_RED,
+            LevelMarker::Goal { .. } => Color::LIME_GREEN,
+            LevelMarker::Trigger { .. } => Color::VIOLET,
+        };
+        gizmos.cuboid(
+            transform.compute_transform() * Transform::from_scale(marker.half_extents() * 2.0),
+            color,
+        );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_selection(
+    mut gizmos: Gizmos,
+    editor: Res<LevelEditor>,
+    drag: Res<GizmoDrag>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    item_query: Query<(Entity, &LevelItem)>,
+    bounds_query: Query<(&GlobalTransform, Option<&Aabb>)>,
+) {
+    let Some(entity) = selected_entity(&editor, &item_query) else {
+        return;
+    };
+    let Ok((transform, aabb)) = bounds_query.get(entity) else {
+        return;
+    };
+    let Ok((_, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+    let transform = transform.compute_transform();
+
+    match (editor.selected, editor.level(), aabb) {
+        (Some(LevelItem::Marker(index)), Some(level), _) => {
+            if let Some(marker) = level.markers.get(index) {
+                gizmos.cuboid(
+                    transform * Transform::from_scale(marker.half_extents() * 2.0 + 0.05),
+                    SELECTION_COLOR,
+                );
+            }
+        }
+        (_, _, Some(aabb)) => {
+            gizmos.cuboid(
+                transform
+                    * Transform::from_translation(aabb.center.into())
+                        .with_scale(Vec3::from(aabb.half_extents) * 2.0 + 0.05),
+                SELECTION_COLOR,
+            );
+        }
+        _ => {}
+    }
+
+    // handles sit where dragging starts from, the saved transform
+    let transform = drag
+        .0
+        .as_ref()
+        .map(|drag| drag.current)
+        .or_else(|| editor.selected_transform())
+        .unwrap_or(transform);
+    let active = drag.0.as_ref().map(|drag| drag.axis).or_else(|| {
+        let ray = cursor_ray(&window_query, &camera_query)?;
+        grabbed_handle(editor.mode, ray, &transform).map(|(axis, _)| axis)
+    });
+    let (axes, length) = handles(editor.mode, &transform, camera_transform.translation());
+    for (index, axis) in axes.into_iter().enumerate() {
+        let color = if active == Some(index) {
+            ACTIVE_HANDLE_COLOR
+        } else {
+            AXIS_COLORS[index]
+        };
+        let tip = transform.translation + axis * length;
+        match editor.mode {
+            GizmoMode::Move => {
+                gizmos.line(transform.translation, tip, color);
+                gizmos.sphere(tip, Quat::IDENTITY, length * 0.05, color);
+            }
+            GizmoMode::Rotate => {
+                gizmos.circle(transform.translation, axis, length, color);
+            }
+            GizmoMode::Scale => {
+                gizmos.line(transform.translation, tip, color);
+                gizmos.cuboid(
+                    Transform::from_translation(tip)
+                        .with_rotation(transform.rotation)
+                        .with_scale(Vec3::splat(length * 0.08)),
+                    color,
+                );
+            }
+        }
+    }
+}
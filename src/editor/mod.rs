@@ -0,0 +1,387 @@
+mod gizmo;
+mod palette;
+
+use crate::actions::{Action, ActionState};
+use crate::camera::MainCamera;
+use crate::goal::RunSet;
+use crate::level::{
+    spawn_level, Level, LevelItem, LevelRoot, LevelSpawned, LevelTransform, LEVEL_FORMAT_VERSION,
+};
+use crate::player::{CharacterMotorSet, PlayerControlSet};
+use crate::third_person_camera::ThirdPersonCamera;
+use bevy::prelude::*;
+use gizmo::GizmoPlugin;
+use palette::PalettePlugin;
+
+pub use gizmo::GizmoMode;
+
+/// Edits kept for undoing, oldest first
+const UNDO_LIMIT: usize = 100;
+/// How far a duplicate is placed from the original
+const DUPLICATE_OFFSET: Vec3 = Vec3::new(1.0, 0.0, 1.0);
+/// Folder the asset server loads from, which levels are saved back into
+#[cfg(not(target_arch = "wasm32"))]
+const ASSET_FOLDER: &str = "assets";
+/// Copy of [`ASSET_FOLDER`] the web build is served from. Saved levels are written to it too when
+/// it exists, so both builds play the same levels
+#[cfg(not(target_arch = "wasm32"))]
+const WEB_ASSET_FOLDER: &str = "static/assets";
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((GizmoPlugin, PalettePlugin))
+            .init_resource::<LevelEditor>()
+            .add_systems(
+                Update,
+                (
+                    toggle_editor,
+                    reopen_reloaded_level.run_if(editor_active),
+                    edit_with_shortcuts.run_if(editor_active),
+                    respawn_edited_level.run_if(editor_active),
+                )
+                    .chain()
+                    .in_set(EditorSet),
+            )
+            // playing is paused while editing, so shortcuts don't also move the player, and the
+            // run isn't timed or ended meanwhile
+            .configure_set(FixedUpdate, PlayerControlSet.run_if(not(editor_active)))
+            .configure_set(FixedUpdate, CharacterMotorSet.run_if(not(editor_active)))
+            .configure_set(FixedUpdate, RunSet.run_if(not(editor_active)));
+    }
+}
+
+/// Opens and closes the editor, and applies its edits to the level
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EditorSet;
+
+/// Lets the level under the [`LevelRoot`] be changed while the game runs, and saved back to its
+/// `.level.ron` file.
+///
+/// Edits are made to a copy of the [`Level`], which is respawned after every edit. The copy is
+/// only written to the asset once saved, so the hot reload that follows matches what's on screen.
+#[derive(Resource, Default)]
+pub struct LevelEditor {
+    active: bool,
+    root: Option<Entity>,
+    /// Asset path of the level being edited
+    path: Option<String>,
+    level: Option<Level>,
+    pub selected: Option<LevelItem>,
+    pub mode: GizmoMode,
+    undo: Vec<Level>,
+    redo: Vec<Level>,
+    respawn: bool,
+    unsaved: bool,
+}
+
+impl LevelEditor {
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    /// The edited copy of the level
+    pub fn level(&self) -> Option<&Level> {
+        self.level.as_ref()
+    }
+
+    /// Whether there are edits that haven't been saved to the level file
+    pub fn unsaved(&self) -> bool {
+        self.unsaved
+    }
+
+    /// Applies `edit` to the level as a single undoable step
+    pub fn edit<R>(&mut self, edit: impl FnOnce(&mut Level) -> R) -> Option<R> {
+        let level = self.level.as_mut()?;
+        self.undo.push(level.clone());
+        if self.undo.len() > UNDO_LIMIT {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+        self.respawn = true;
+        self.unsaved = true;
+        Some(edit(level))
+    }
+
+    pub fn undo(&mut self) {
+        let (Some(level), Some(previous)) = (self.level.as_mut(), self.undo.pop()) else {
+            return;
+        };
+        self.redo.push(std::mem::replace(level, previous));
+        self.after_history_step();
+    }
+
+    pub fn redo(&mut self) {
+        let (Some(level), Some(next)) = (self.level.as_mut(), self.redo.pop()) else {
+            return;
+        };
+        self.undo.push(std::mem::replace(level, next));
+        self.after_history_step();
+    }
+
+    fn after_history_step(&mut self) {
+        self.respawn = true;
+        self.unsaved = true;
+        if let (Some(level), Some(selected)) = (&mut self.level, self.selected) {
+            if level.transform_mut(selected).is_none() {
+                self.selected = None;
+            }
+        }
+    }
+
+    /// Copies the selected entry next to itself and selects the copy
+    pub fn duplicate_selected(&mut self) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        self.selected = self.edit(|level| duplicate(level, selected)).flatten();
+    }
+
+    pub fn delete_selected(&mut self) {
+        let Some(selected) = self.selected.take() else {
+            return;
+        };
+        self.edit(|level| match selected {
+            LevelItem::Object(index) => remove(&mut level.objects, index),
+            LevelItem::Scene(index) => remove(&mut level.scenes, index),
+            LevelItem::Course(index) => remove(&mut level.courses, index),
+            LevelItem::Marker(index) => remove(&mut level.markers, index),
+        });
+    }
+
+    /// Transform the selected entry is saved with, which an animated entity, like a moving
+    /// platform, only starts from
+    pub fn selected_transform(&self) -> Option<Transform> {
+        let transform = self.level.as_ref()?.transform(self.selected?)?;
+        Some((*transform).into())
+    }
+
+    /// Moves the selected entry to `transform`
+    pub fn set_selected_transform(&mut self, transform: Transform) {
+        let Some(selected) = self.selected else {
+            return;
+        };
+        self.edit(|level| {
+            if let Some(level_transform) = level.transform_mut(selected) {
+                *level_transform = transform.into();
+            }
+        });
+    }
+
+    fn open(&mut self, root: Entity, path: Option<String>, level: Level) {
+        *self = Self {
+            active: true,
+            root: Some(root),
+            path,
+            level: Some(level),
+            mode: self.mode,
+            ..default()
+        };
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn save(&mut self) {
+        warn!("Levels can't be saved from the browser");
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&mut self) {
+        let (Some(level), Some(path)) = (&mut self.level, &self.path) else {
+            return;
+        };
+        level.version = LEVEL_FORMAT_VERSION;
+        let ron = match ron::ser::to_string_pretty(level, default()) {
+            Ok(ron) => ron + "\n",
+            Err(error) => {
+                warn!("Could not save {path}: {error}");
+                return;
+            }
+        };
+
+        let base_path = bevy::asset::FileAssetIo::get_base_path();
+        let web_folder = base_path.join(WEB_ASSET_FOLDER);
+        let folders = std::iter::once(base_path.join(ASSET_FOLDER))
+            .chain(web_folder.is_dir().then_some(web_folder));
+        let mut saved = true;
+        for folder in folders {
+            let file = folder.join(path);
+            match std::fs::write(&file, &ron) {
+                Ok(()) => info!("Saved {}", file.display()),
+                Err(error) => {
+                    warn!("Could not save {}: {error}", file.display());
+                    saved = false;
+                }
+            }
+        }
+        if saved {
+            self.unsaved = false;
+        }
+    }
+}
+
+fn duplicate(level: &mut Level, item: LevelItem) -> Option<LevelItem> {
+    fn duplicate_in<T: Clone>(
+        list: &mut Vec<T>,
+        index: usize,
+        transform: impl Fn(&mut T) -> &mut LevelTransform,
+    ) -> Option<usize> {
+        let mut copy = list.get(index)?.clone();
+        transform(&mut copy).position += DUPLICATE_OFFSET;
+        list.push(copy);
+        Some(list.len() - 1)
+    }
+
+    match item {
+        LevelItem::Object(index) => {
+            duplicate_in(&mut level.objects, index, |o| &mut o.transform).map(LevelItem::Object)
+        }
+        LevelItem::Scene(index) => {
+            duplicate_in(&mut level.scenes, index, |s| &mut s.transform).map(LevelItem::Scene)
+        }
+        LevelItem::Course(index) => {
+            duplicate_in(&mut level.courses, index, |c| &mut c.transform).map(LevelItem::Course)
+        }
+        LevelItem::Marker(index) => {
+            duplicate_in(&mut level.markers, index, |m| m.transform_mut()).map(LevelItem::Marker)
+        }
+    }
+}
+
+fn remove<T>(list: &mut Vec<T>, index: usize) {
+    if index < list.len() {
+        list.remove(index);
+    }
+}
+
+pub fn editor_active(editor: Res<LevelEditor>) -> bool {
+    editor.active
+}
+
+fn toggle_editor(
+    actions: Res<ActionState>,
+    mut editor: ResMut<LevelEditor>,
+    root_query: Query<(Entity, &LevelRoot)>,
+    levels: Res<Assets<Level>>,
+    asset_server: Res<AssetServer>,
+    mut camera_query: Query<&mut ThirdPersonCamera, With<MainCamera>>,
+) {
+    if !actions.just_pressed(Action::ToggleEditor) {
+        return;
+    }
+
+    if editor.active {
+        if editor.unsaved {
+            warn!(
+                "Closed the editor with unsaved changes to {:?}",
+                editor.path
+            );
+        }
+        editor.active = false;
+        editor.selected = None;
+    } else {
+        let Some((root, level_root, level)) = root_query
+            .iter()
+            .find_map(|(root, level_root)| Some((root, level_root, levels.get(&level_root.0)?)))
+        else {
+            warn!("There is no loaded level to edit");
+            return;
+        };
+        let path = asset_server
+            .get_handle_path(&level_root.0)
+            .map(|path| path.path().to_string_lossy().into_owned());
+        editor.open(root, path, level.clone());
+    }
+
+    // the cursor is needed to pick things, and locked again for playing
+    for mut camera in camera_query.iter_mut() {
+        camera.cursor_lock_active = !editor.active;
+    }
+}
+
+/// Starts over from the file when another level is loaded or the level is restarted
+fn reopen_reloaded_level(
+    mut spawned_reader: EventReader<LevelSpawned>,
+    mut editor: ResMut<LevelEditor>,
+    root_query: Query<&LevelRoot>,
+    levels: Res<Assets<Level>>,
+    asset_server: Res<AssetServer>,
+) {
+    for spawned in spawned_reader.iter() {
+        if !spawned.first_load || editor.root != Some(spawned.root) {
+            continue;
+        }
+        let Some((level_root, level)) = root_query
+            .get(spawned.root)
+            .ok()
+            .and_then(|level_root| Some((level_root, levels.get(&level_root.0)?)))
+        else {
+            continue;
+        };
+        let path = asset_server
+            .get_handle_path(&level_root.0)
+            .map(|path| path.path().to_string_lossy().into_owned());
+        editor.open(spawned.root, path, level.clone());
+    }
+}
+
+fn edit_with_shortcuts(actions: Res<ActionState>, mut editor: ResMut<LevelEditor>) {
+    if actions.just_pressed(Action::EditorMoveMode) {
+        editor.mode = GizmoMode::Move;
+    }
+    if actions.just_pressed(Action::EditorRotateMode) {
+        editor.mode = GizmoMode::Rotate;
+    }
+    if actions.just_pressed(Action::EditorScaleMode) {
+        editor.mode = GizmoMode::Scale;
+    }
+    if actions.just_pressed(Action::EditorDelete) {
+        editor.delete_selected();
+    }
+    if actions.just_pressed(Action::EditorUndo) {
+        editor.undo();
+    }
+    if actions.just_pressed(Action::EditorRedo) {
+        editor.redo();
+    }
+    if actions.just_pressed(Action::EditorDuplicate) {
+        editor.duplicate_selected();
+    }
+    if actions.just_pressed(Action::EditorSave) {
+        editor.save();
+    }
+}
+
+fn respawn_edited_level(
+    mut commands: Commands,
+    mut editor: ResMut<LevelEditor>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut spawned_writer: EventWriter<LevelSpawned>,
+) {
+    if !editor.respawn {
+        return;
+    }
+    editor.respawn = false;
+    let (Some(root), Some(level)) = (editor.root, &editor.level) else {
+        return;
+    };
+    let Some(mut root_commands) = commands.get_entity(root) else {
+        return;
+    };
+
+    root_commands.despawn_descendants();
+    spawn_level(
+        &mut commands,
+        root,
+        level,
+        &mut meshes,
+        &mut materials,
+        &asset_server,
+    );
+    spawned_writer.send(LevelSpawned {
+        root,
+        first_load: false,
+    });
+}
@@ -0,0 +1,290 @@
+use super::{EditorSet, GizmoMode, LevelEditor};
+use crate::camera::MainCamera;
+use crate::checkpoint::Checkpoint;
+use crate::level::{Level, LevelItem, LevelMarker, LevelObject, LevelShape, LevelTransform};
+use crate::player::PLAYER_TAG;
+use crate::ui::PersistentUi;
+use bevy::prelude::*;
+
+/// How far in front of the camera new entries are placed
+const PLACE_DISTANCE: f32 = 10.0;
+/// Grid new entries are snapped to
+const PLACE_SNAP: f32 = 0.5;
+/// Material new platforms get, when the level has one by that name
+const PLATFORM_MATERIAL: &str = "platform";
+const MARKER_HALF_EXTENTS: Vec3 = Vec3::new(2.0, 1.0, 2.0);
+
+const BUTTON_COLOR: Color = Color::DARK_GRAY;
+const BUTTON_HOVERED_COLOR: Color = Color::GRAY;
+
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, create_palette).add_systems(
+            Update,
+            (
+                show_palette,
+                press_palette_buttons.before(EditorSet),
+                show_editor_status,
+            ),
+        );
+    }
+}
+
+/// Lists what can be added to the level while the editor is open
+#[derive(Component)]
+pub struct EditorPalette;
+
+#[derive(Component)]
+struct EditorStatus;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum PaletteEntry {
+    Platform,
+    Checkpoint,
+    KillVolume,
+    Goal,
+    Trigger,
+}
+
+impl PaletteEntry {
+    const ALL: [PaletteEntry; 5] = [
+        PaletteEntry::Platform,
+        PaletteEntry::Checkpoint,
+        PaletteEntry::KillVolume,
+        PaletteEntry::Goal,
+        PaletteEntry::Trigger,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            PaletteEntry::Platform => "Platform",
+            PaletteEntry::Checkpoint => "Checkpoint",
+            PaletteEntry::KillVolume => "Kill Volume",
+            PaletteEntry::Goal => "Goal",
+            PaletteEntry::Trigger => "Trigger",
+        }
+    }
+}
+
+fn create_palette(mut cmd: Commands) {
+    cmd.spawn((
+        Name::new("Editor Palette"),
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(16.),
+                left: Val::Px(16.),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.),
+                padding: UiRect::all(Val::Px(8.)),
+                ..default()
+            },
+            background_color: Color::BLACK.with_a(0.6).into(),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        EditorPalette,
+        PersistentUi,
+    ))
+    .with_children(|parent| {
+        for entry in PaletteEntry::ALL {
+            parent
+                .spawn((
+                    Name::new(format!("{} Button", entry.label())),
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(160.),
+                            height: Val::Px(40.),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: BUTTON_COLOR.into(),
+                        ..default()
+                    },
+                    entry,
+                    PersistentUi,
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section(
+                            entry.label(),
+                            TextStyle {
+                                font_size: 24.,
+                                ..default()
+                            },
+                        ),
+                        PersistentUi,
+                    ));
+                });
+        }
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 18.,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                max_width: Val::Px(160.),
+                ..default()
+            }),
+            EditorStatus,
+            PersistentUi,
+        ));
+    });
+}
+
+fn show_palette(
+    editor: Res<LevelEditor>,
+    mut palette_q: Query<&mut Visibility, With<EditorPalette>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    for mut visibility in palette_q.iter_mut() {
+        *visibility = if editor.active() {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+fn show_editor_status(editor: Res<LevelEditor>, mut text_q: Query<&mut Text, With<EditorStatus>>) {
+    if !editor.is_changed() || !editor.active() {
+        return;
+    }
+    let mode = match editor.mode {
+        GizmoMode::Move => "Move",
+        GizmoMode::Rotate => "Rotate",
+        GizmoMode::Scale => "Scale",
+    };
+    let selected = match (editor.selected, editor.level()) {
+        (Some(LevelItem::Object(index)), Some(level)) => level
+            .objects
+            .get(index)
+            .map(|object| object.name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "Object".to_string()),
+        (Some(LevelItem::Scene(index)), Some(level)) => level
+            .scenes
+            .get(index)
+            .map(|scene| scene.path.clone())
+            .unwrap_or_default(),
+        (Some(LevelItem::Course(_)), _) => "Course".to_string(),
+        (Some(LevelItem::Marker(index)), Some(level)) => match level.markers.get(index) {
+            Some(LevelMarker::Checkpoint { .. }) => "Checkpoint",
+            Some(LevelMarker::KillVolume { .. }) => "Kill Volume",
+            Some(LevelMarker::Goal { .. }) => "Goal",
+            Some(LevelMarker::Trigger { .. }) => "Trigger",
+            None => "",
+        }
+        .to_string(),
+        _ => "Nothing".to_string(),
+    };
+    let status = format!(
+        "{mode} (1 Move, 2 Rotate, 3 Scale)\n\
+         Selected: {selected}\n\
+         Ctrl+D duplicate, Del delete\n\
+         Ctrl+Z undo, Ctrl+Y redo\n\
+         Ctrl+S save{}",
+        if editor.unsaved() { " *" } else { "" },
+    );
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = status.clone();
+    }
+}
+
+fn add_marker(level: &mut Level, marker: LevelMarker) -> LevelItem {
+    level.markers.push(marker);
+    LevelItem::Marker(level.markers.len() - 1)
+}
+
+/// A spot in front of the camera, on the placement grid
+fn placement(camera: &GlobalTransform) -> LevelTransform {
+    let position = camera.translation() + camera.forward() * PLACE_DISTANCE;
+    LevelTransform {
+        position: (position / PLACE_SNAP).round() * PLACE_SNAP,
+        ..default()
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn press_palette_buttons(
+    mut button_q: Query<(&PaletteEntry, &Interaction, &mut BackgroundColor), Changed<Interaction>>,
+    mut editor: ResMut<LevelEditor>,
+    camera_q: Query<&GlobalTransform, With<MainCamera>>,
+) {
+    for (entry, interaction, mut background) in button_q.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Hovered => BUTTON_HOVERED_COLOR,
+            Interaction::Pressed | Interaction::None => BUTTON_COLOR,
+        };
+        if *interaction != Interaction::Pressed || !editor.active() {
+            continue;
+        }
+        let Ok(camera) = camera_q.get_single() else {
+            continue;
+        };
+        let transform = placement(camera);
+
+        editor.selected = editor.edit(|level| match entry {
+            PaletteEntry::Platform => {
+                let material = level
+                    .materials
+                    .contains_key(PLATFORM_MATERIAL)
+                    .then(|| PLATFORM_MATERIAL.to_string());
+                level.objects.push(LevelObject {
+                    name: "Platform".to_string(),
+                    shape: LevelShape::Box {
+                        size: Vec3::new(4.0, 0.5, 4.0),
+                    },
+                    transform,
+                    material,
+                    collider: true,
+                    motion: None,
+                    surface: None,
+                    crumbling: None,
+                    timed: None,
+                });
+                LevelItem::Object(level.objects.len() - 1)
+            }
+            PaletteEntry::Checkpoint => add_marker(
+                level,
+                LevelMarker::Checkpoint {
+                    transform,
+                    half_extents: MARKER_HALF_EXTENTS,
+                    respawn_offset: Checkpoint::default().respawn_offset,
+                },
+            ),
+            PaletteEntry::KillVolume => add_marker(
+                level,
+                LevelMarker::KillVolume {
+                    transform,
+                    half_extents: MARKER_HALF_EXTENTS,
+                },
+            ),
+            PaletteEntry::Goal => add_marker(
+                level,
+                LevelMarker::Goal {
+                    transform,
+                    half_extents: MARKER_HALF_EXTENTS,
+                },
+            ),
+            PaletteEntry::Trigger => add_marker(
+                level,
+                LevelMarker::Trigger {
+                    transform,
+                    half_extents: MARKER_HALF_EXTENTS,
+                    tags: vec![PLAYER_TAG.to_string()],
+                    one_shot: false,
+                    cooldown: 0.0,
+                },
+            ),
+        });
+    }
+}
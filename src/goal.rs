@@ -0,0 +1,239 @@
+use crate::actions::{Action, FixedActionState};
+use crate::checkpoint::{kill_players, PlayerDied};
+use crate::level::{Level, LevelRoot, LevelSpawned};
+use crate::physics::{FixedStepSet, PHYSICS_TIMESTEP};
+use crate::player::Player;
+use crate::trigger::{TriggerEntered, TriggerSet};
+use bevy::{prelude::*, utils::HashMap};
+
+/// Key the best times are stored under in the browser's local storage
+#[cfg(target_arch = "wasm32")]
+const BEST_TIMES_KEY: &str = "best_times";
+/// File the best times are stored in, relative to the working directory
+#[cfg(not(target_arch = "wasm32"))]
+const BEST_TIMES_FILE: &str = "best_times.json";
+
+pub struct GoalPlugin;
+
+impl Plugin for GoalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunTimer>()
+            .init_resource::<BestTimes>()
+            .add_event::<LevelCompleted>()
+            .add_systems(Startup, load_best_times)
+            .add_systems(
+                FixedUpdate,
+                (
+                    start_run_timer
+                        .in_set(RunSet)
+                        .in_set(FixedStepSet::Simulate),
+                    (count_deaths, reach_goals)
+                        .chain()
+                        .after(TriggerSet)
+                        .after(kill_players)
+                        .in_set(RunSet)
+                        .in_set(FixedStepSet::Record),
+                ),
+            )
+            .add_systems(Update, reset_run_timer);
+    }
+}
+
+/// Systems that time a run and end it, through goals, checkpoints and deaths
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RunSet;
+
+/// [`TriggerVolume`](crate::trigger::TriggerVolume) that completes the level once the player
+/// enters it
+#[derive(Component)]
+pub struct Goal;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RunState {
+    /// The level was just entered and the player hasn't moved yet
+    #[default]
+    Waiting,
+    Running,
+    /// A [`Goal`] was reached
+    Finished,
+}
+
+/// Time and deaths of the current attempt at the level.
+///
+/// Starts on the first movement input and counts fixed ticks, so a run takes the same time
+/// however the frames fall.
+#[derive(Resource, Default)]
+pub struct RunTimer {
+    state: RunState,
+    ticks: u64,
+    deaths: u32,
+}
+
+impl RunTimer {
+    pub fn state(&self) -> RunState {
+        self.state
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        (self.ticks as f64 * PHYSICS_TIMESTEP as f64) as f32
+    }
+
+    pub fn deaths(&self) -> u32 {
+        self.deaths
+    }
+}
+
+/// Fastest completion time of every level, keyed by the level's asset path
+#[derive(Resource, Default)]
+pub struct BestTimes(HashMap<String, f32>);
+
+impl BestTimes {
+    pub fn get(&self, level: &str) -> Option<f32> {
+        self.0.get(level).copied()
+    }
+
+    /// Keeps `time` if it beats the best time of `level`, returning whether it did
+    pub fn record(&mut self, level: &str, time: f32) -> bool {
+        if self.get(level).is_some_and(|best| best <= time) {
+            return false;
+        }
+        self.0.insert(level.to_string(), time);
+        true
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct LevelCompleted {
+    pub player: Entity,
+    /// Asset path of the completed level
+    pub level: String,
+    /// Level to go to from here, see [`Level::next_level`]
+    pub next_level: Option<String>,
+    /// Seconds the run took
+    pub time: f32,
+    pub deaths: u32,
+    /// Best time including this run
+    pub best_time: f32,
+    /// Whether this run set the best time
+    pub new_best: bool,
+}
+
+fn load_best_times(mut best_times: ResMut<BestTimes>) {
+    #[cfg(target_arch = "wasm32")]
+    let json = web_sys::window()
+        .and_then(|window| window.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item(BEST_TIMES_KEY).ok().flatten());
+    #[cfg(not(target_arch = "wasm32"))]
+    let json = std::fs::read_to_string(BEST_TIMES_FILE).ok();
+
+    let Some(json) = json else {
+        return;
+    };
+    match serde_json::from_str(&json) {
+        Ok(times) => best_times.0 = times,
+        Err(error) => warn!("Ignoring unreadable best times: {error}"),
+    }
+}
+
+fn save_best_times(best_times: &BestTimes) {
+    let json = match serde_json::to_string(&best_times.0) {
+        Ok(json) => json,
+        Err(error) => {
+            warn!("Could not save best times: {error}");
+            return;
+        }
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        let saved = web_sys::window()
+            .and_then(|window| window.local_storage().ok().flatten())
+            .is_some_and(|storage| storage.set_item(BEST_TIMES_KEY, &json).is_ok());
+        if !saved {
+            warn!("Could not save best times to local storage");
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Err(error) = std::fs::write(BEST_TIMES_FILE, json) {
+        warn!("Could not save best times to {BEST_TIMES_FILE}: {error}");
+    }
+}
+
+fn reset_run_timer(mut spawned_reader: EventReader<LevelSpawned>, mut run: ResMut<RunTimer>) {
+    // a hot reload keeps the run going
+    if spawned_reader.iter().any(|spawned| spawned.first_load) {
+        *run = RunTimer::default();
+    }
+}
+
+fn start_run_timer(mut run: ResMut<RunTimer>, actions: Res<FixedActionState>) {
+    if run.state == RunState::Waiting
+        && (actions.axis(Action::Move) != Vec2::ZERO
+            || actions.just_pressed(Action::Jump)
+            || actions.just_pressed(Action::Dash))
+    {
+        run.state = RunState::Running;
+    }
+    if run.state == RunState::Running {
+        run.ticks += 1;
+    }
+}
+
+fn count_deaths(mut died_reader: EventReader<PlayerDied>, mut run: ResMut<RunTimer>) {
+    for _ in died_reader.iter() {
+        if run.state == RunState::Running {
+            run.deaths += 1;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn reach_goals(
+    mut entered_reader: EventReader<TriggerEntered>,
+    goal_query: Query<(), With<Goal>>,
+    player_query: Query<(), With<Player>>,
+    parent_query: Query<&Parent>,
+    root_query: Query<&LevelRoot>,
+    levels: Res<Assets<Level>>,
+    asset_server: Res<AssetServer>,
+    mut run: ResMut<RunTimer>,
+    mut best_times: ResMut<BestTimes>,
+    mut completed_writer: EventWriter<LevelCompleted>,
+) {
+    for entered in entered_reader.iter() {
+        if run.state != RunState::Running
+            || !goal_query.contains(entered.trigger)
+            || !player_query.contains(entered.entity)
+        {
+            continue;
+        }
+        let Some(root) = parent_query
+            .iter_ancestors(entered.trigger)
+            .find_map(|entity| root_query.get(entity).ok())
+        else {
+            continue;
+        };
+        let level = asset_server
+            .get_handle_path(&root.0)
+            .map(|path| path.path().to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        run.state = RunState::Finished;
+        let time = run.elapsed_seconds();
+        let new_best = best_times.record(&level, time);
+        if new_best {
+            save_best_times(&best_times);
+        }
+        completed_writer.send(LevelCompleted {
+            player: entered.entity,
+            next_level: levels
+                .get(&root.0)
+                .and_then(|level| level.next_level.clone()),
+            best_time: best_times.get(&level).unwrap_or(time),
+            level,
+            time,
+            deaths: run.deaths,
+            new_best,
+        });
+    }
+}
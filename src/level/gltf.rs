@@ -0,0 +1,159 @@
+use crate::trigger::TriggerVolume;
+use bevy::{gltf::GltfExtras, prelude::*, render::primitives::Aabb};
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+pub struct GltfCollidersPlugin;
+
+impl Plugin for GltfCollidersPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, generate_gltf_colliders);
+    }
+}
+
+/// Root of a glTF scene spawned from a level. Its nodes get colliders based on their names
+/// and extras
+#[derive(Component)]
+pub struct LevelScene;
+
+/// How a node's meshes become colliders
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GltfColliderShape {
+    /// Exact triangle mesh. Only for static geometry
+    Trimesh,
+    /// Convex hull of the mesh
+    Convex,
+    /// Bounding box of the mesh
+    Box,
+    /// Bounding box [`TriggerVolume`] that detects overlaps without blocking
+    Sensor,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GltfBody {
+    #[default]
+    Fixed,
+    Kinematic,
+}
+
+/// Collider settings read from a node's custom properties, e.g.
+/// `{"collider": "convex", "body": "kinematic", "render": true}`
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(default)]
+struct GltfColliderExtras {
+    collider: Option<GltfColliderShape>,
+    body: GltfBody,
+    render: Option<bool>,
+}
+
+/// Collider settings of a node.
+///
+/// A name suffix (`_col`, `_convex`, `_box` or `_sensor`, optionally followed by Blender's
+/// `.001` style numbering) marks a collision-only node whose meshes aren't rendered. Extras
+/// add a collider to a node that stays visible unless `"render": false` is set.
+fn node_collider_settings(
+    name: &str,
+    extras: Option<&GltfExtras>,
+) -> Option<(GltfColliderShape, GltfBody, bool)> {
+    let extras = extras
+        .and_then(|extras| serde_json::from_str::<GltfColliderExtras>(&extras.value).ok())
+        .unwrap_or_default();
+
+    let name = match name.rsplit_once('.') {
+        Some((base, number)) if number.chars().all(|c| c.is_ascii_digit()) => base,
+        _ => name,
+    };
+    let from_name = [
+        ("_col", GltfColliderShape::Trimesh),
+        ("_convex", GltfColliderShape::Convex),
+        ("_box", GltfColliderShape::Box),
+        ("_sensor", GltfColliderShape::Sensor),
+    ]
+    .into_iter()
+    .find(|(suffix, _)| name.ends_with(suffix))
+    .map(|(_, shape)| shape);
+
+    let shape = extras.collider.or(from_name)?;
+    let render = extras
+        .render
+        .unwrap_or(from_name.is_none() && shape != GltfColliderShape::Sensor);
+    Some((shape, extras.body, render))
+}
+
+fn mesh_collider(shape: GltfColliderShape, mesh: &Mesh, aabb: Option<&Aabb>) -> Option<Collider> {
+    match shape {
+        GltfColliderShape::Trimesh => {
+            Collider::from_bevy_mesh(mesh, &ComputedColliderShape::TriMesh)
+        }
+        GltfColliderShape::Convex => {
+            Collider::from_bevy_mesh(mesh, &ComputedColliderShape::ConvexHull)
+        }
+        GltfColliderShape::Box | GltfColliderShape::Sensor => {
+            let aabb = aabb.copied().or_else(|| mesh.compute_aabb())?;
+            let half_extents = Vec3::from(aabb.half_extents);
+            Some(Collider::compound(vec![(
+                aabb.center.into(),
+                Quat::IDENTITY,
+                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+            )]))
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn generate_gltf_colliders(
+    mut commands: Commands,
+    node_query: Query<
+        (Entity, &Name, Option<&GltfExtras>, &Children),
+        (Added<Name>, Without<Handle<Mesh>>),
+    >,
+    parent_query: Query<&Parent>,
+    level_scene_query: Query<(), With<LevelScene>>,
+    mesh_query: Query<(&Handle<Mesh>, Option<&Aabb>)>,
+    meshes: Res<Assets<Mesh>>,
+) {
+    for (node, name, extras, children) in node_query.iter() {
+        let Some((shape, body, render)) = node_collider_settings(name.as_str(), extras) else {
+            continue;
+        };
+        let in_level_scene = parent_query
+            .iter_ancestors(node)
+            .any(|ancestor| level_scene_query.contains(ancestor));
+        if !in_level_scene {
+            continue;
+        }
+
+        let mut has_collider = false;
+        for &child in children.iter() {
+            let Ok((mesh_handle, aabb)) = mesh_query.get(child) else {
+                continue;
+            };
+            let Some(collider) = meshes
+                .get(mesh_handle)
+                .and_then(|mesh| mesh_collider(shape, mesh, aabb))
+            else {
+                warn!("Could not build a {shape:?} collider for glTF node {name}");
+                continue;
+            };
+
+            let mut child_commands = commands.entity(child);
+            child_commands.insert(collider);
+            if shape == GltfColliderShape::Sensor {
+                child_commands.insert((Sensor, TriggerVolume::default()));
+            }
+            if !render {
+                child_commands.remove::<(Handle<Mesh>, Handle<StandardMaterial>)>();
+            }
+            has_collider = true;
+        }
+
+        if has_collider && shape != GltfColliderShape::Sensor {
+            commands.entity(node).insert(match body {
+                GltfBody::Fixed => RigidBody::Fixed,
+                GltfBody::Kinematic => RigidBody::KinematicPositionBased,
+            });
+        }
+    }
+}
@@ -0,0 +1,891 @@
+mod gltf;
+
+use crate::checkpoint::{Checkpoint, KillPlane, KillVolume, RespawnPoint};
+use crate::course::Course;
+use crate::goal::Goal;
+use crate::lighting::{spawn_sky, CastsShadows, TimeOfDay};
+use crate::physics::{RenderInterpolation, SimulationClock};
+use crate::platform::{CrumblingPlatform, MovingPlatform, TimedPlatform};
+use crate::player::{CharacterMotorState, Player, SurfaceType, PLAYER_TAG};
+use crate::trigger::TriggerVolume;
+use bevy::{
+    asset::{AssetLoader, HandleId, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::{BoxedFuture, HashMap},
+};
+use bevy_rapier3d::prelude::*;
+use gltf::GltfCollidersPlugin;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use gltf::{GltfBody, GltfColliderShape, LevelScene};
+
+/// Version written by this build. Files with a newer version are rejected
+pub const LEVEL_FORMAT_VERSION: u32 = 9;
+
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(GltfCollidersPlugin)
+            .add_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .add_event::<LevelSpawned>()
+            .add_event::<LoadLevel>()
+            .add_systems(Startup, load_main_level)
+            .add_systems(
+                Update,
+                (
+                    load_requested_levels,
+                    spawn_loaded_levels,
+                    (move_players_to_level_spawn, restart_level_clock),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// A level as stored in a `.level.ron` file.
+///
+/// Everything is spawned as children of a [`LevelRoot`], and respawned whenever the file is
+/// saved.
+#[derive(Clone, Debug, Serialize, Deserialize, TypeUuid, TypePath)]
+#[uuid = "b4a7d3e2-0f5c-4d8e-9a61-3c2e7f4b8d15"]
+pub struct Level {
+    pub version: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub player_spawn: PlayerSpawn,
+    /// Players below this height die. Keeps the current [`KillPlane`] when missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kill_height: Option<f32>,
+    /// Replaced by the day and night cycle when `time_of_day` is set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ambient_light: Option<LevelAmbientLight>,
+    /// Adds a sun, a moon and a sky lit by the time of day, added in version 8
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_of_day: Option<LevelTimeOfDay>,
+    /// Materials referenced by name from [`LevelObject::material`], sorted so saved levels
+    /// diff cleanly
+    #[serde(default)]
+    pub materials: BTreeMap<String, LevelMaterial>,
+    #[serde(default)]
+    pub lights: Vec<LevelLight>,
+    #[serde(default)]
+    pub objects: Vec<LevelObject>,
+    #[serde(default)]
+    pub markers: Vec<LevelMarker>,
+    /// glTF scenes, added in version 2
+    #[serde(default)]
+    pub scenes: Vec<LevelSceneInstance>,
+    /// Generated platform courses, added in version 3
+    #[serde(default)]
+    pub courses: Vec<LevelCourse>,
+    /// Asset path of the level offered once this one is completed, added in version 7
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_level: Option<String>,
+}
+
+/// Translation, rotation in degrees and scale
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelTransform {
+    pub position: Vec3,
+    /// Euler angles in degrees, applied in Y, X, Z order
+    pub rotation: Vec3,
+    pub scale: Vec3,
+}
+
+impl Default for LevelTransform {
+    fn default() -> Self {
+        Self {
+            position: Vec3::ZERO,
+            rotation: Vec3::ZERO,
+            scale: Vec3::ONE,
+        }
+    }
+}
+
+impl From<LevelTransform> for Transform {
+    fn from(transform: LevelTransform) -> Self {
+        let rotation = transform.rotation * std::f32::consts::PI / 180.0;
+        Transform {
+            translation: transform.position,
+            rotation: Quat::from_euler(EulerRot::YXZ, rotation.y, rotation.x, rotation.z),
+            scale: transform.scale,
+        }
+    }
+}
+
+impl From<Transform> for LevelTransform {
+    fn from(transform: Transform) -> Self {
+        let (y, x, z) = transform.rotation.to_euler(EulerRot::YXZ);
+        Self {
+            position: transform.translation,
+            rotation: Vec3::new(x, y, z) * 180.0 / std::f32::consts::PI,
+            scale: transform.scale,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerSpawn {
+    pub position: Vec3,
+    /// Facing direction in degrees around the Y axis
+    pub yaw: f32,
+}
+
+impl PlayerSpawn {
+    pub fn transform(&self) -> Transform {
+        Transform::from_translation(self.position)
+            .with_rotation(Quat::from_rotation_y(self.yaw.to_radians()))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LevelAmbientLight {
+    pub color: Color,
+    pub brightness: f32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct LevelTimeOfDay {
+    /// Hour the level starts at, where 12 is noon
+    pub hour: f32,
+    /// Keeps the time at `hour` instead of letting the day go by
+    #[serde(default)]
+    pub fixed: bool,
+    /// Real seconds a full day takes
+    #[serde(default = "default_day_length")]
+    pub day_length: f32,
+}
+
+fn default_day_length() -> f32 {
+    TimeOfDay::default().day_length
+}
+
+impl From<LevelTimeOfDay> for TimeOfDay {
+    fn from(time_of_day: LevelTimeOfDay) -> Self {
+        TimeOfDay {
+            hour: time_of_day.hour,
+            day_length: time_of_day.day_length,
+            paused: time_of_day.fixed,
+            ..default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelMaterial {
+    pub color: Color,
+    pub emissive: Color,
+    pub metallic: f32,
+    pub roughness: f32,
+    pub unlit: bool,
+}
+
+impl Default for LevelMaterial {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            emissive: Color::BLACK,
+            metallic: 0.0,
+            roughness: 0.5,
+            unlit: false,
+        }
+    }
+}
+
+impl From<&LevelMaterial> for StandardMaterial {
+    fn from(material: &LevelMaterial) -> Self {
+        StandardMaterial {
+            base_color: material.color,
+            emissive: material.emissive,
+            metallic: material.metallic,
+            perceptual_roughness: material.roughness,
+            unlit: material.unlit,
+            alpha_mode: if material.color.a() < 1.0 {
+                AlphaMode::Blend
+            } else {
+                AlphaMode::Opaque
+            },
+            ..default()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LevelLight {
+    Directional {
+        /// Euler angles in degrees, applied in Y, X, Z order
+        rotation: Vec3,
+        #[serde(default = "default_directional_illuminance")]
+        illuminance: f32,
+        #[serde(default)]
+        shadows: bool,
+    },
+    Point {
+        position: Vec3,
+        #[serde(default = "default_point_intensity")]
+        intensity: f32,
+        #[serde(default = "default_point_range")]
+        range: f32,
+        #[serde(default = "default_light_color")]
+        color: Color,
+        #[serde(default)]
+        shadows: bool,
+    },
+}
+
+fn default_directional_illuminance() -> f32 {
+    DirectionalLight::default().illuminance
+}
+
+fn default_point_intensity() -> f32 {
+    PointLight::default().intensity
+}
+
+fn default_point_range() -> f32 {
+    PointLight::default().range
+}
+
+fn default_light_color() -> Color {
+    Color::WHITE
+}
+
+/// Primitive used for both the mesh and the collider of a [`LevelObject`]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum LevelShape {
+    Box {
+        size: Vec3,
+    },
+    /// Square floor with a 1 unit thick collider below it
+    Plane {
+        size: f32,
+    },
+    Sphere {
+        radius: f32,
+    },
+    Cylinder {
+        radius: f32,
+        height: f32,
+    },
+}
+
+impl LevelShape {
+    pub fn mesh(&self) -> Mesh {
+        match *self {
+            LevelShape::Box { size } => Mesh::from(shape::Box::new(size.x, size.y, size.z)),
+            LevelShape::Plane { size } => Mesh::from(shape::Plane::from_size(size)),
+            LevelShape::Sphere { radius } => Mesh::from(shape::UVSphere {
+                radius,
+                ..default()
+            }),
+            LevelShape::Cylinder { radius, height } => Mesh::from(shape::Cylinder {
+                radius,
+                height,
+                ..default()
+            }),
+        }
+    }
+
+    pub fn collider(&self) -> Collider {
+        match *self {
+            LevelShape::Box { size } => Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+            LevelShape::Plane { size } => Collider::compound(vec![(
+                Vec3::NEG_Y * 0.5,
+                Quat::IDENTITY,
+                Collider::cuboid(size / 2.0, 0.5, size / 2.0),
+            )]),
+            LevelShape::Sphere { radius } => Collider::ball(radius),
+            LevelShape::Cylinder { radius, height } => Collider::cylinder(height / 2.0, radius),
+        }
+    }
+}
+
+/// A piece of static geometry
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelObject {
+    #[serde(default)]
+    pub name: String,
+    pub shape: LevelShape,
+    #[serde(default)]
+    pub transform: LevelTransform,
+    /// Key into [`Level::materials`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    /// Whether the object gets a collider matching its shape
+    #[serde(default = "default_true")]
+    pub collider: bool,
+    /// Makes the object a kinematic platform, added in version 4
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motion: Option<MovingPlatform>,
+    /// How the player moves on the object, added in version 5. Also picks a default material
+    /// when `material` is missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<SurfaceType>,
+    /// Gives way when stood on, added in version 6
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crumbling: Option<CrumblingPlatform>,
+    /// Turns solid and back on a beat, added in version 6
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timed: Option<TimedPlatform>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A glTF scene placed in the level, e.g. `"levels/course.glb#Scene0"`.
+///
+/// Nodes get colliders from their names or extras, see [`GltfColliderShape`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelSceneInstance {
+    pub path: String,
+    #[serde(default)]
+    pub transform: LevelTransform,
+}
+
+/// A generated [`Course`]. Its first platform's top is at `transform`, and it heads towards
+/// the transform's -Z
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LevelCourse {
+    pub seed: u64,
+    /// Number of platforms, or `None` for an endless course
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub length: Option<u32>,
+    #[serde(default)]
+    pub transform: LevelTransform,
+    /// Key into [`Level::materials`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub material: Option<String>,
+    /// A checkpoint is placed on every n-th platform. `0` for none
+    #[serde(default = "default_checkpoint_every")]
+    pub checkpoint_every: u32,
+    /// Makes the last platform a goal, added in version 7
+    #[serde(default)]
+    pub goal: bool,
+}
+
+fn default_checkpoint_every() -> u32 {
+    10
+}
+
+/// Invisible gameplay volumes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum LevelMarker {
+    Checkpoint {
+        transform: LevelTransform,
+        half_extents: Vec3,
+        /// Where the player reappears, relative to the checkpoint
+        #[serde(default = "default_respawn_offset")]
+        respawn_offset: Vec3,
+    },
+    KillVolume {
+        transform: LevelTransform,
+        half_extents: Vec3,
+    },
+    /// Completes the level, added in version 7
+    Goal {
+        transform: LevelTransform,
+        half_extents: Vec3,
+    },
+    /// A [`TriggerVolume`] for anything listening to trigger events, added in version 9
+    Trigger {
+        transform: LevelTransform,
+        half_extents: Vec3,
+        /// Only bodies with one of these tags are reported. Empty for any body
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        one_shot: bool,
+        /// Seconds after an entry during which new entries are ignored
+        #[serde(default)]
+        cooldown: f32,
+    },
+}
+
+impl LevelMarker {
+    pub fn transform(&self) -> &LevelTransform {
+        match self {
+            LevelMarker::Checkpoint { transform, .. }
+            | LevelMarker::KillVolume { transform, .. }
+            | LevelMarker::Goal { transform, .. }
+            | LevelMarker::Trigger { transform, .. } => transform,
+        }
+    }
+
+    pub fn transform_mut(&mut self) -> &mut LevelTransform {
+        match self {
+            LevelMarker::Checkpoint { transform, .. }
+            | LevelMarker::KillVolume { transform, .. }
+            | LevelMarker::Goal { transform, .. }
+            | LevelMarker::Trigger { transform, .. } => transform,
+        }
+    }
+
+    pub fn half_extents(&self) -> Vec3 {
+        match *self {
+            LevelMarker::Checkpoint { half_extents, .. }
+            | LevelMarker::KillVolume { half_extents, .. }
+            | LevelMarker::Goal { half_extents, .. }
+            | LevelMarker::Trigger { half_extents, .. } => half_extents,
+        }
+    }
+}
+
+fn default_respawn_offset() -> Vec3 {
+    Checkpoint::default().respawn_offset
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let level = ron::de::from_bytes::<Level>(bytes)?;
+            if level.version > LEVEL_FORMAT_VERSION {
+                return Err(bevy::asset::Error::msg(format!(
+                    "{} uses level format version {}, but only versions up to {} are supported",
+                    load_context.path().display(),
+                    level.version,
+                    LEVEL_FORMAT_VERSION
+                )));
+            }
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// Parent of everything spawned from a [`Level`]
+#[derive(Component)]
+pub struct LevelRoot(pub Handle<Level>);
+
+/// Which entry of the [`Level`] an entity was spawned from, by index into its list
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LevelItem {
+    Object(usize),
+    Scene(usize),
+    Course(usize),
+    Marker(usize),
+}
+
+impl Level {
+    /// Transform of the entry `item` was spawned from, if it still exists
+    pub fn transform(&self, item: LevelItem) -> Option<&LevelTransform> {
+        match item {
+            LevelItem::Object(index) => self.objects.get(index).map(|o| &o.transform),
+            LevelItem::Scene(index) => self.scenes.get(index).map(|s| &s.transform),
+            LevelItem::Course(index) => self.courses.get(index).map(|c| &c.transform),
+            LevelItem::Marker(index) => self.markers.get(index).map(|m| m.transform()),
+        }
+    }
+
+    /// Mutable [`Level::transform`]
+    pub fn transform_mut(&mut self, item: LevelItem) -> Option<&mut LevelTransform> {
+        match item {
+            LevelItem::Object(index) => self.objects.get_mut(index).map(|o| &mut o.transform),
+            LevelItem::Scene(index) => self.scenes.get_mut(index).map(|s| &mut s.transform),
+            LevelItem::Course(index) => self.courses.get_mut(index).map(|c| &mut c.transform),
+            LevelItem::Marker(index) => self.markers.get_mut(index).map(|m| m.transform_mut()),
+        }
+    }
+}
+
+/// The level last spawned under a [`LevelRoot`]
+#[derive(Component)]
+struct SpawnedLevel(HandleId);
+
+/// Sent after a level has been (re)spawned under `root`
+#[derive(Event)]
+pub struct LevelSpawned {
+    pub root: Entity,
+    /// Whether the level was just loaded or restarted, rather than hot reloaded
+    pub first_load: bool,
+}
+
+/// Replaces the level under every [`LevelRoot`] with the one at `path`, and moves players to
+/// its spawn. Loading the current level again restarts it
+#[derive(Event, Clone, Debug)]
+pub struct LoadLevel {
+    pub path: String,
+}
+
+fn load_main_level(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Name::new("Level"),
+        SpatialBundle::default(),
+        LevelRoot(asset_server.load("levels/main.level.ron")),
+    ));
+}
+
+fn load_requested_levels(
+    mut load_reader: EventReader<LoadLevel>,
+    mut root_query: Query<&mut LevelRoot>,
+    asset_server: Res<AssetServer>,
+) {
+    for load in load_reader.iter() {
+        for mut root in root_query.iter_mut() {
+            // reassigned even when unchanged, so the level is spawned again
+            root.0 = asset_server.load(&load.path);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_loaded_levels(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Level>>,
+    root_query: Query<(Entity, &LevelRoot, Option<&SpawnedLevel>)>,
+    changed_root_query: Query<(Entity, &LevelRoot), Changed<LevelRoot>>,
+    levels: Res<Assets<Level>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+    mut kill_plane: ResMut<KillPlane>,
+    mut ambient_light: ResMut<AmbientLight>,
+    mut time_of_day: ResMut<TimeOfDay>,
+    mut spawned_writer: EventWriter<LevelSpawned>,
+) {
+    let mut to_spawn = Vec::new();
+    for event in events.iter() {
+        let (handle, first_load) = match event {
+            AssetEvent::Created { handle } => (handle, true),
+            AssetEvent::Modified { handle } => (handle, false),
+            AssetEvent::Removed { .. } => continue,
+        };
+        for (root, level_root, spawned) in root_query.iter() {
+            // the event comes a frame after the level could first be spawned below
+            let already_spawned =
+                first_load && spawned.is_some_and(|spawned| spawned.0 == handle.id());
+            if level_root.0 == *handle && !already_spawned {
+                to_spawn.push((root, first_load));
+            }
+        }
+    }
+    // roots pointed at a level that is already loaded get no asset event
+    for (root, level_root) in changed_root_query.iter() {
+        if levels.contains(&level_root.0) && !to_spawn.iter().any(|(other, _)| *other == root) {
+            to_spawn.push((root, true));
+        }
+    }
+
+    for (root, first_load) in to_spawn {
+        let Some((level, handle)) = root_query
+            .get(root)
+            .ok()
+            .and_then(|(_, level_root, _)| Some((levels.get(&level_root.0)?, level_root.0.id())))
+        else {
+            continue;
+        };
+
+        commands
+            .entity(root)
+            .despawn_descendants()
+            .insert(SpawnedLevel(handle));
+        spawn_level(
+            &mut commands,
+            root,
+            level,
+            &mut meshes,
+            &mut materials,
+            &asset_server,
+        );
+        spawned_writer.send(LevelSpawned { root, first_load });
+
+        if let Some(height) = level.kill_height {
+            kill_plane.height = height;
+        }
+        if let Some(ambient) = level.ambient_light {
+            ambient_light.color = ambient.color;
+            ambient_light.brightness = ambient.brightness;
+        }
+        if let Some(level_time_of_day) = level.time_of_day {
+            *time_of_day = level_time_of_day.into();
+        }
+    }
+}
+
+/// Spawns the contents of `level` as children of `root`
+pub fn spawn_level(
+    commands: &mut Commands,
+    root: Entity,
+    level: &Level,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    asset_server: &AssetServer,
+) {
+    let material_handles = level
+        .materials
+        .iter()
+        .map(|(name, material)| (name.clone(), materials.add(material.into())))
+        .collect::<HashMap<_, _>>();
+    let default_material = materials.add(StandardMaterial::default());
+    let material = |name: &Option<String>| match name {
+        Some(name) => material_handles.get(name).cloned().unwrap_or_else(|| {
+            warn!("Level {:?} has no material named {name:?}", level.name);
+            default_material.clone()
+        }),
+        None => default_material.clone(),
+    };
+    let mut surface_materials = HashMap::new();
+    for surface in level.objects.iter().filter_map(|object| object.surface) {
+        surface_materials
+            .entry(std::mem::discriminant(&surface))
+            .or_insert_with(|| materials.add(surface.material()));
+    }
+
+    commands.entity(root).with_children(|parent| {
+        for (index, object) in level.objects.iter().enumerate() {
+            let mut entity = parent.spawn((
+                Name::new(if object.name.is_empty() {
+                    "Object".to_string()
+                } else {
+                    object.name.clone()
+                }),
+                PbrBundle {
+                    transform: object.transform.into(),
+                    mesh: meshes.add(object.shape.mesh()),
+                    material: match (&object.material, object.surface) {
+                        (None, Some(surface)) => {
+                            surface_materials[&std::mem::discriminant(&surface)].clone()
+                        }
+                        (name, _) => material(name),
+                    },
+                    ..default()
+                },
+                LevelItem::Object(index),
+            ));
+            if object.collider {
+                entity.insert(object.shape.collider());
+            }
+            if let Some(surface) = object.surface {
+                entity.insert(surface);
+            }
+            if let Some(crumbling) = &object.crumbling {
+                entity.insert(crumbling.clone());
+            }
+            if let Some(timed) = &object.timed {
+                entity.insert(timed.clone());
+            }
+            match &object.motion {
+                // no body, see MovingPlatform
+                Some(motion) => {
+                    entity.insert((motion.clone(), RenderInterpolation::default()));
+                }
+                None if object.collider => {
+                    entity.insert(RigidBody::Fixed);
+                }
+                None => {}
+            }
+        }
+
+        for light in &level.lights {
+            match *light {
+                LevelLight::Directional {
+                    rotation,
+                    illuminance,
+                    shadows,
+                } => {
+                    let mut light = parent.spawn((
+                        Name::new("Directional Light"),
+                        DirectionalLightBundle {
+                            directional_light: DirectionalLight {
+                                illuminance,
+                                ..default()
+                            },
+                            transform: LevelTransform {
+                                rotation,
+                                ..default()
+                            }
+                            .into(),
+                            ..default()
+                        },
+                    ));
+                    if shadows {
+                        light.insert(CastsShadows);
+                    }
+                }
+                LevelLight::Point {
+                    position,
+                    intensity,
+                    range,
+                    color,
+                    shadows,
+                } => {
+                    let mut light = parent.spawn((
+                        Name::new("Point Light"),
+                        PointLightBundle {
+                            point_light: PointLight {
+                                intensity,
+                                range,
+                                color,
+                                ..default()
+                            },
+                            transform: Transform::from_translation(position),
+                            ..default()
+                        },
+                    ));
+                    if shadows {
+                        light.insert(CastsShadows);
+                    }
+                }
+            }
+        }
+
+        if level.time_of_day.is_some() {
+            spawn_sky(parent, meshes, materials);
+        }
+
+        for (index, scene) in level.scenes.iter().enumerate() {
+            parent.spawn((
+                Name::new(scene.path.clone()),
+                SceneBundle {
+                    scene: asset_server.load(&scene.path),
+                    transform: scene.transform.into(),
+                    ..default()
+                },
+                LevelScene,
+                LevelItem::Scene(index),
+            ));
+        }
+
+        for (index, course) in level.courses.iter().enumerate() {
+            parent.spawn((
+                Name::new(format!("Course {}", course.seed)),
+                SpatialBundle::from_transform(course.transform.into()),
+                Course {
+                    seed: course.seed,
+                    length: course.length,
+                    checkpoint_every: course.checkpoint_every,
+                    goal: course.goal,
+                    material: material(&course.material),
+                },
+                LevelItem::Course(index),
+            ));
+        }
+
+        for (index, marker) in level.markers.iter().enumerate() {
+            let item = LevelItem::Marker(index);
+            match *marker {
+                LevelMarker::Checkpoint {
+                    transform,
+                    half_extents,
+                    respawn_offset,
+                } => {
+                    parent.spawn((
+                        Name::new("Checkpoint"),
+                        TransformBundle::from_transform(transform.into()),
+                        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                        Sensor,
+                        TriggerVolume::tagged(PLAYER_TAG),
+                        Checkpoint { respawn_offset },
+                        item,
+                    ));
+                }
+                LevelMarker::KillVolume {
+                    transform,
+                    half_extents,
+                } => {
+                    parent.spawn((
+                        Name::new("Kill Volume"),
+                        TransformBundle::from_transform(transform.into()),
+                        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                        Sensor,
+                        TriggerVolume::tagged(PLAYER_TAG),
+                        KillVolume,
+                        item,
+                    ));
+                }
+                LevelMarker::Goal {
+                    transform,
+                    half_extents,
+                } => {
+                    parent.spawn((
+                        Name::new("Goal"),
+                        TransformBundle::from_transform(transform.into()),
+                        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                        Sensor,
+                        TriggerVolume::tagged(PLAYER_TAG),
+                        Goal,
+                        item,
+                    ));
+                }
+                LevelMarker::Trigger {
+                    transform,
+                    half_extents,
+                    ref tags,
+                    one_shot,
+                    cooldown,
+                } => {
+                    parent.spawn((
+                        Name::new("Trigger"),
+                        TransformBundle::from_transform(transform.into()),
+                        Collider::cuboid(half_extents.x, half_extents.y, half_extents.z),
+                        Sensor,
+                        TriggerVolume {
+                            tags: tags.clone(),
+                            one_shot,
+                            cooldown,
+                        },
+                        item,
+                    ));
+                }
+            }
+        }
+    });
+}
+
+/// Restarts the level's timing with each attempt, as moving platforms restart when spawned
+fn restart_level_clock(
+    mut spawned_reader: EventReader<LevelSpawned>,
+    mut clock: ResMut<SimulationClock>,
+) {
+    if spawned_reader.iter().any(|spawned| spawned.first_load) {
+        clock.restart_level();
+    }
+}
+
+fn move_players_to_level_spawn(
+    mut spawned_reader: EventReader<LevelSpawned>,
+    root_query: Query<&LevelRoot>,
+    levels: Res<Assets<Level>>,
+    mut player_query: Query<
+        (&mut Transform, &mut RespawnPoint, &mut CharacterMotorState),
+        With<Player>,
+    >,
+) {
+    for spawned in spawned_reader.iter() {
+        let Some(level) = root_query
+            .get(spawned.root)
+            .ok()
+            .and_then(|root| levels.get(&root.0))
+        else {
+            continue;
+        };
+        let spawn = level.player_spawn.transform();
+
+        for (mut transform, mut respawn_point, mut motor) in player_query.iter_mut() {
+            // checkpoints were respawned too, so the old one is gone
+            *respawn_point = RespawnPoint::from_transform(spawn);
+            if spawned.first_load {
+                *transform = spawn;
+                *motor = CharacterMotorState::default();
+            }
+        }
+    }
+}
@@ -1,11 +1,22 @@
+pub mod actions;
 pub mod camera;
+pub mod checkpoint;
+pub mod course;
+pub mod editor;
+pub mod goal;
+pub mod level;
 pub mod lifetime;
+pub mod lighting;
+pub mod particles;
+pub mod physics;
+pub mod platform;
 pub mod player;
 pub mod third_person_camera;
+pub mod trigger;
 pub mod ui;
 pub mod virtual_joystick;
-pub mod world;
 
+use actions::*;
 use bevy::{
     asset::ChangeWatcher,
     input::common_conditions::input_toggle_active,
@@ -13,18 +24,27 @@ use bevy::{
     window::{close_on_esc, CursorGrabMode, PresentMode, PrimaryWindow, WindowFocused, WindowMode},
 };
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
-use bevy_rapier3d::prelude::*;
 use camera::*;
+use checkpoint::*;
+use course::*;
+use editor::*;
+use goal::*;
+use level::*;
 use lifetime::*;
+use lighting::*;
+use particles::*;
+use physics::*;
+use platform::*;
 use player::*;
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 use third_person_camera::*;
+use trigger::*;
 use ui::*;
 use virtual_joystick::*;
 use wasm_bindgen::prelude::*;
-use world::*;
 
-#[derive(Default, Reflect, Hash, Clone, PartialEq, Eq)]
+#[derive(Default, Reflect, Hash, Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub enum JoystickControllerID {
     #[default]
     MoveJoystick,
@@ -55,18 +75,31 @@ pub fn main_js() -> Result<(), JsValue> {
                     ..default()
                 }),
             WorldInspectorPlugin::new().run_if(input_toggle_active(false, KeyCode::Grave)),
-            RapierPhysicsPlugin::<NoUserData>::default(),
+            PhysicsPlugin,
             // RapierDebugRenderPlugin::default(),
             ThirdPersonCameraPlugin,
             VirtualJoystickPlugin::<JoystickControllerID>::default(),
         ))
         .add_plugins((
+            ActionsPlugin,
             LifetimePlugin,
+            ParticlesPlugin,
             CameraPlugin,
-            WorldPlugin,
+            LightingPlugin,
+            LevelPlugin,
+            CoursePlugin,
+            PlatformPlugin,
+            TriggerPlugin,
             PlayerPlugin,
+            CheckpointPlugin,
+            GoalPlugin,
             UIPlugin,
+            EditorPlugin,
         ))
+        .configure_set(
+            Update,
+            RenderInterpolationSet.before(ThirdPersonCameraSyncSet),
+        )
         .add_systems(Startup, lock_and_hide_cursor)
         .add_systems(
             Update,
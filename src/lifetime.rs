@@ -1,3 +1,4 @@
+use crate::physics::FixedStepSet;
 use bevy::prelude::*;
 use std::time::Duration;
 
@@ -5,10 +6,12 @@ pub struct LifetimePlugin;
 
 impl Plugin for LifetimePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, lifetime_system);
+        app.add_systems(FixedUpdate, lifetime_system.in_set(FixedStepSet::Simulate));
     }
 }
 
+/// Despawns the entity after a duration of simulation time, counted in fixed ticks so it
+/// always expires on the same tick
 #[derive(Component)]
 pub struct Lifetime {
     timer: Timer,
@@ -29,10 +32,10 @@ impl Lifetime {
 pub fn lifetime_system(
     mut commands: Commands,
     mut query: Query<(Entity, &mut Lifetime)>,
-    time: Res<Time>,
+    fixed_time: Res<FixedTime>,
 ) {
     for (entity, mut lifetime) in query.iter_mut() {
-        lifetime.timer.tick(time.delta());
+        lifetime.timer.tick(fixed_time.period);
         if lifetime.timer.just_finished() {
             commands.entity(entity).despawn_recursive();
         }
@@ -0,0 +1,448 @@
+use crate::camera::MainCamera;
+use crate::third_person_camera::ThirdPersonCameraSyncSet;
+use bevy::{
+    pbr::{NotShadowCaster, NotShadowReceiver},
+    prelude::*,
+    render::{mesh::VertexAttributeValues, render_resource::Face},
+};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+/// Radius of the sky dome. Kept well inside the camera's far plane
+const SKY_RADIUS: f32 = 500.0;
+
+/// Largest change of a sky colour channel that is ignored, since recolouring the dome re-uploads
+/// its vertices. About one step of an 8 bit channel
+const SKY_COLOR_THRESHOLD: f32 = 1.0 / 255.0;
+
+pub struct LightingPlugin;
+
+impl Plugin for LightingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AmbientLight>()
+            .init_resource::<ClearColor>()
+            .init_resource::<TimeOfDay>()
+            .init_resource::<DayNightCycle>()
+            .init_resource::<ShadowSettings>()
+            .add_systems(
+                Update,
+                (
+                    advance_time_of_day,
+                    (light_sun_and_moon, light_sky).after(advance_time_of_day),
+                    follow_camera_with_sky.after(ThirdPersonCameraSyncSet),
+                    apply_shadow_settings,
+                ),
+            );
+    }
+}
+
+/// The hour of the day, which lights every [`Sun`], [`Moon`] and [`SkyDome`] through the
+/// [`DayNightCycle`]
+#[derive(Resource, Clone, Debug)]
+pub struct TimeOfDay {
+    /// Hour in `0..24`, where 12 is noon
+    pub hour: f32,
+    /// Real seconds a full day takes at a `speed` of 1
+    pub day_length: f32,
+    /// Multiplier on how fast time passes
+    pub speed: f32,
+    /// Pins the time to the current hour
+    pub paused: bool,
+}
+
+impl Default for TimeOfDay {
+    fn default() -> Self {
+        Self {
+            hour: 10.0,
+            day_length: 600.0,
+            speed: 1.0,
+            paused: false,
+        }
+    }
+}
+
+impl TimeOfDay {
+    /// Time pinned to `hour`
+    pub fn fixed(hour: f32) -> Self {
+        Self {
+            hour,
+            paused: true,
+            ..default()
+        }
+    }
+}
+
+/// Values that can be blended between two keyframes of an [`HourCurve`]
+pub trait Mix: Copy {
+    fn mix(self, other: Self, t: f32) -> Self;
+}
+
+impl Mix for f32 {
+    fn mix(self, other: Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl Mix for Color {
+    fn mix(self, other: Self, t: f32) -> Self {
+        let [r, g, b, a] = self.as_rgba_f32();
+        let [other_r, other_g, other_b, other_a] = other.as_rgba_f32();
+        Color::rgba(
+            r.mix(other_r, t),
+            g.mix(other_g, t),
+            b.mix(other_b, t),
+            a.mix(other_a, t),
+        )
+    }
+}
+
+/// Values at given hours, blended linearly in between and wrapping around midnight
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(
+    from = "Vec<(f32, T)>",
+    into = "Vec<(f32, T)>",
+    bound(
+        serialize = "T: Serialize + Clone",
+        deserialize = "T: Deserialize<'de>"
+    )
+)]
+pub struct HourCurve<T>(Vec<(f32, T)>);
+
+impl<T> HourCurve<T> {
+    /// Keys can be given in any order
+    pub fn new(mut keys: Vec<(f32, T)>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self(keys)
+    }
+
+    /// Keys sorted by hour
+    pub fn keys(&self) -> &[(f32, T)] {
+        &self.0
+    }
+}
+
+impl<T> From<Vec<(f32, T)>> for HourCurve<T> {
+    fn from(keys: Vec<(f32, T)>) -> Self {
+        Self::new(keys)
+    }
+}
+
+impl<T> From<HourCurve<T>> for Vec<(f32, T)> {
+    fn from(curve: HourCurve<T>) -> Self {
+        curve.0
+    }
+}
+
+impl<T: Mix + Default> HourCurve<T> {
+    pub fn sample(&self, hour: f32) -> T {
+        let keys = &self.0;
+        let (Some(&first), Some(&last)) = (keys.first(), keys.last()) else {
+            return T::default();
+        };
+
+        let hour = hour.rem_euclid(24.0);
+        // around midnight the neighbouring keys are on the previous or next day
+        let (from_hour, from) = keys
+            .iter()
+            .rev()
+            .find(|(key_hour, _)| *key_hour <= hour)
+            .copied()
+            .unwrap_or((last.0 - 24.0, last.1));
+        let (to_hour, to) = keys
+            .iter()
+            .find(|(key_hour, _)| *key_hour > hour)
+            .copied()
+            .unwrap_or((first.0 + 24.0, first.1));
+        from.mix(
+            to,
+            (hour - from_hour) / (to_hour - from_hour).max(f32::EPSILON),
+        )
+    }
+}
+
+/// Colour and brightness of a [`Sun`] or [`Moon`] over the day
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CelestialLight {
+    pub color: HourCurve<Color>,
+    /// Lux, see [`DirectionalLight::illuminance`]
+    pub illuminance: HourCurve<f32>,
+}
+
+/// How the lighting changes over the day
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct DayNightCycle {
+    pub sun: CelestialLight,
+    pub moon: CelestialLight,
+    pub ambient_color: HourCurve<Color>,
+    pub ambient_brightness: HourCurve<f32>,
+    /// Colour of the sky straight up
+    pub sky_zenith: HourCurve<Color>,
+    /// Colour of the sky at the horizon, also used as the clear colour
+    pub sky_horizon: HourCurve<Color>,
+    /// Degrees the path of the sun and moon leans away from straight overhead
+    pub tilt: f32,
+}
+
+impl Default for DayNightCycle {
+    fn default() -> Self {
+        let dusk = Color::rgb(1.0, 0.45, 0.2);
+        let morning = Color::rgb(1.0, 0.9, 0.75);
+        Self {
+            sun: CelestialLight {
+                color: HourCurve::new(vec![
+                    (6.0, dusk),
+                    (8.0, morning),
+                    (12.0, Color::WHITE),
+                    (16.0, morning),
+                    (18.0, dusk),
+                ]),
+                illuminance: HourCurve::new(vec![
+                    // dark while below the horizon
+                    (6.0, 0.0),
+                    (7.5, 20_000.0),
+                    (12.0, 100_000.0),
+                    (16.5, 20_000.0),
+                    (18.0, 0.0),
+                ]),
+            },
+            moon: CelestialLight {
+                color: HourCurve::new(vec![(0.0, Color::rgb(0.6, 0.7, 1.0))]),
+                illuminance: HourCurve::new(vec![
+                    (6.0, 0.0),
+                    (18.0, 0.0),
+                    (20.0, 5_000.0),
+                    (4.0, 5_000.0),
+                ]),
+            },
+            ambient_color: HourCurve::new(vec![
+                (0.0, Color::rgb(0.3, 0.35, 0.6)),
+                (6.0, Color::rgb(0.8, 0.6, 0.5)),
+                (12.0, Color::WHITE),
+                (18.0, Color::rgb(0.8, 0.6, 0.5)),
+            ]),
+            ambient_brightness: HourCurve::new(vec![
+                (0.0, 0.02),
+                (6.0, 0.04),
+                (12.0, 0.08),
+                (18.0, 0.04),
+            ]),
+            sky_zenith: HourCurve::new(vec![
+                (0.0, Color::rgb(0.01, 0.01, 0.05)),
+                (6.0, Color::rgb(0.25, 0.3, 0.55)),
+                (12.0, Color::rgb(0.2, 0.45, 0.9)),
+                (18.0, Color::rgb(0.25, 0.25, 0.5)),
+            ]),
+            sky_horizon: HourCurve::new(vec![
+                (0.0, Color::rgb(0.03, 0.03, 0.08)),
+                (6.0, Color::rgb(1.0, 0.55, 0.35)),
+                (12.0, Color::rgb(0.7, 0.85, 1.0)),
+                (18.0, Color::rgb(1.0, 0.45, 0.3)),
+            ]),
+            tilt: 30.0,
+        }
+    }
+}
+
+/// Whether lights marked with [`CastsShadows`] render shadows. Off by default with the
+/// `low-end` feature, for mobile builds
+#[derive(Resource)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            enabled: !cfg!(feature = "low-end"),
+        }
+    }
+}
+
+/// Light that renders shadows while [`ShadowSettings::enabled`] is set
+#[derive(Component)]
+pub struct CastsShadows;
+
+/// Directional light moved and coloured by the [`DayNightCycle`]
+#[derive(Component)]
+pub struct Sun;
+
+/// Directional light opposite the [`Sun`]
+#[derive(Component)]
+pub struct Moon;
+
+/// Gradient from the horizon to the zenith, kept around the main camera
+#[derive(Component, Default)]
+pub struct SkyDome {
+    /// Zenith and horizon colours the mesh was last coloured with
+    applied: Option<(Color, Color)>,
+}
+
+/// Everything needed for a day and night cycle: a [`Sun`], a [`Moon`] and a [`SkyDome`]
+pub fn spawn_sky(
+    parent: &mut ChildBuilder,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    parent.spawn((
+        Name::new("Sun"),
+        DirectionalLightBundle::default(),
+        Sun,
+        CastsShadows,
+    ));
+    parent.spawn((Name::new("Moon"), DirectionalLightBundle::default(), Moon));
+    parent.spawn((
+        Name::new("Sky"),
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::UVSphere {
+                radius: SKY_RADIUS,
+                sectors: 32,
+                stacks: 16,
+            })),
+            material: materials.add(StandardMaterial {
+                unlit: true,
+                fog_enabled: false,
+                // seen from the inside
+                cull_mode: Some(Face::Front),
+                ..default()
+            }),
+            ..default()
+        },
+        SkyDome::default(),
+        NotShadowCaster,
+        NotShadowReceiver,
+    ));
+}
+
+fn advance_time_of_day(mut time_of_day: ResMut<TimeOfDay>, time: Res<Time>) {
+    if time_of_day.paused {
+        return;
+    }
+    let hours = time.delta_seconds() * time_of_day.speed * 24.0 / time_of_day.day_length;
+    time_of_day.hour = (time_of_day.hour + hours).rem_euclid(24.0);
+}
+
+/// Direction towards the sun, rising in +X at 6 and setting in -X at 18
+fn sun_direction(hour: f32, tilt: f32) -> Vec3 {
+    let angle = (hour - 6.0) / 24.0 * TAU;
+    Quat::from_rotation_x(-tilt.to_radians()) * Vec3::new(angle.cos(), angle.sin(), 0.0)
+}
+
+#[allow(clippy::type_complexity)]
+fn light_sun_and_moon(
+    time_of_day: Res<TimeOfDay>,
+    cycle: Res<DayNightCycle>,
+    mut sun_query: Query<(&mut DirectionalLight, &mut Transform), (With<Sun>, Without<Moon>)>,
+    mut moon_query: Query<(&mut DirectionalLight, &mut Transform), With<Moon>>,
+    mut ambient_light: ResMut<AmbientLight>,
+) {
+    if sun_query.is_empty() {
+        // levels without a day and night cycle keep their own ambient light
+        return;
+    }
+    let hour = time_of_day.hour;
+    let sun_direction = sun_direction(hour, cycle.tilt);
+
+    let lights = sun_query
+        .iter_mut()
+        .map(|light| (light, &cycle.sun, sun_direction))
+        .chain(
+            moon_query
+                .iter_mut()
+                .map(|light| (light, &cycle.moon, -sun_direction)),
+        );
+    for ((mut light, mut transform), curves, direction) in lights {
+        light.color = curves.color.sample(hour);
+        light.illuminance = curves.illuminance.sample(hour);
+        // directional lights shine along their forward axis
+        transform.rotation = Quat::from_rotation_arc(Vec3::NEG_Z, -direction);
+    }
+
+    ambient_light.color = cycle.ambient_color.sample(hour);
+    ambient_light.brightness = cycle.ambient_brightness.sample(hour);
+}
+
+fn light_sky(
+    time_of_day: Res<TimeOfDay>,
+    cycle: Res<DayNightCycle>,
+    mut sky_query: Query<(&mut SkyDome, &Handle<Mesh>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    let zenith = cycle.sky_zenith.sample(time_of_day.hour);
+    let horizon = cycle.sky_horizon.sample(time_of_day.hour);
+
+    // the clear colour shows below the dome, so only blend it in with a sky
+    if !sky_query.is_empty() && clear_color.0 != horizon {
+        clear_color.0 = horizon;
+    }
+    for (mut sky, mesh) in sky_query.iter_mut() {
+        let changed = sky.applied.is_none_or(|(applied_zenith, applied_horizon)| {
+            color_changed(applied_zenith, zenith) || color_changed(applied_horizon, horizon)
+        });
+        if !changed {
+            continue;
+        }
+        let Some(mesh) = meshes.get_mut(mesh) else {
+            continue;
+        };
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+        let colors = positions
+            .iter()
+            .map(|position| {
+                // most of the blend happens close to the horizon, like a real sky
+                let height = (position[1] / SKY_RADIUS).max(0.0).sqrt();
+                horizon.mix(zenith, height).as_linear_rgba_f32()
+            })
+            .collect::<Vec<_>>();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+        sky.applied = Some((zenith, horizon));
+    }
+}
+
+fn color_changed(from: Color, to: Color) -> bool {
+    from.as_rgba_f32()
+        .iter()
+        .zip(to.as_rgba_f32())
+        .any(|(from, to)| (from - to).abs() > SKY_COLOR_THRESHOLD)
+}
+
+fn follow_camera_with_sky(
+    camera_query: Query<&GlobalTransform, With<MainCamera>>,
+    mut sky_query: Query<(&mut Transform, Option<&Parent>), With<SkyDome>>,
+    parent_query: Query<&GlobalTransform>,
+) {
+    let Ok(camera) = camera_query.get_single() else {
+        return;
+    };
+    for (mut transform, parent) in sky_query.iter_mut() {
+        let parent_transform = parent
+            .and_then(|parent| parent_query.get(parent.get()).ok())
+            .copied()
+            .unwrap_or_default();
+        transform.translation = parent_transform
+            .affine()
+            .inverse()
+            .transform_point3(camera.translation());
+    }
+}
+
+fn apply_shadow_settings(
+    settings: Res<ShadowSettings>,
+    mut directional_query: Query<(&mut DirectionalLight, Ref<CastsShadows>)>,
+    mut point_query: Query<(&mut PointLight, Ref<CastsShadows>)>,
+) {
+    for (mut light, casts_shadows) in directional_query.iter_mut() {
+        if settings.is_changed() || casts_shadows.is_added() {
+            light.shadows_enabled = settings.enabled;
+        }
+    }
+    for (mut light, casts_shadows) in point_query.iter_mut() {
+        if settings.is_changed() || casts_shadows.is_added() {
+            light.shadows_enabled = settings.enabled;
+        }
+    }
+}
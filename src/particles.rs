@@ -0,0 +1,295 @@
+use bevy::{pbr::NotShadowCaster, prelude::*};
+use rand::prelude::*;
+use std::f32::consts::TAU;
+
+/// Number of materials a colour gradient is split into. Particles of one emitter share them
+const GRADIENT_STEPS: usize = 8;
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ParticleBurst>()
+            .init_resource::<ParticleMesh>()
+            .add_systems(
+                Update,
+                (spawn_particle_pools, emit_particles, simulate_particles).chain(),
+            );
+    }
+}
+
+/// Emits CPU simulated particles without physics.
+///
+/// Particles are taken from a pool of `max_particles` entities spawned with the emitter, so
+/// emitting never spawns or despawns entities. When the pool is exhausted the oldest particle
+/// is reused. All particles of an emitter share one mesh and a handful of materials.
+#[derive(Component, Clone)]
+pub struct ParticleEmitter {
+    /// Particles emitted per second from the emitter's position
+    pub rate: f32,
+    /// Particles emitted by each [`ParticleBurst`]
+    pub burst: u32,
+    /// Emission direction in the emitter's local space
+    pub direction: Vec3,
+    /// Half angle, in radians, of the cone particles are emitted in around the direction
+    pub cone_angle: f32,
+    pub min_speed: f32,
+    pub max_speed: f32,
+    /// Seconds a particle lives for
+    pub lifetime: f32,
+    /// Acceleration applied to every particle
+    pub gravity: Vec3,
+    /// How quickly particles slow down
+    pub drag: f32,
+    pub start_color: Color,
+    pub end_color: Color,
+    pub start_size: f32,
+    pub end_size: f32,
+    /// Size of the pool. Colours and pool size are only read when the emitter is added
+    pub max_particles: usize,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            rate: 0.0,
+            burst: 16,
+            direction: Vec3::Y,
+            cone_angle: 0.5,
+            min_speed: 1.0,
+            max_speed: 2.0,
+            lifetime: 1.0,
+            gravity: Vec3::ZERO,
+            drag: 0.0,
+            start_color: Color::WHITE,
+            end_color: Color::WHITE.with_a(0.0),
+            start_size: 0.1,
+            end_size: 0.1,
+            max_particles: 64,
+        }
+    }
+}
+
+impl ParticleEmitter {
+    fn sample_velocity(&self, direction: Vec3, rng: &mut impl Rng) -> Vec3 {
+        let axis = direction.try_normalize().unwrap_or(Vec3::Y);
+        // uniform over the spherical cap around +Y, then rotated onto the axis
+        let cos_theta = 1.0 - rng.gen::<f32>() * (1.0 - self.cone_angle.cos());
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let phi = rng.gen::<f32>() * TAU;
+        let local = Vec3::new(sin_theta * phi.cos(), cos_theta, sin_theta * phi.sin());
+        let speed = self.min_speed + rng.gen::<f32>() * (self.max_speed - self.min_speed);
+        Quat::from_rotation_arc(Vec3::Y, axis) * local * speed
+    }
+}
+
+/// Emits `burst` particles of `emitter` at once, from `origin` in a cone around `direction`,
+/// both in world space
+#[derive(Event)]
+pub struct ParticleBurst {
+    pub emitter: Entity,
+    pub origin: Vec3,
+    pub direction: Vec3,
+}
+
+#[derive(Component)]
+struct ParticlePool {
+    particles: Vec<Entity>,
+    next: usize,
+    materials: Vec<Handle<StandardMaterial>>,
+    rate_accumulator: f32,
+}
+
+impl ParticlePool {
+    fn take(&mut self) -> Option<Entity> {
+        let entity = *self.particles.get(self.next)?;
+        self.next = (self.next + 1) % self.particles.len();
+        Some(entity)
+    }
+}
+
+/// One pooled particle, hidden while it isn't alive
+#[derive(Component)]
+pub struct Particle {
+    emitter: Entity,
+    alive: bool,
+    age: f32,
+    velocity: Vec3,
+}
+
+#[derive(Resource)]
+struct ParticleMesh(Handle<Mesh>);
+
+impl FromWorld for ParticleMesh {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        Self(meshes.add(Mesh::from(shape::Cube::new(1.0))))
+    }
+}
+
+fn spawn_particle_pools(
+    mut commands: Commands,
+    emitter_query: Query<(Entity, &ParticleEmitter), Added<ParticleEmitter>>,
+    mesh: Res<ParticleMesh>,
+    mut material_assets: ResMut<Assets<StandardMaterial>>,
+) {
+    for (emitter_entity, emitter) in emitter_query.iter() {
+        let start = Vec4::from(emitter.start_color.as_rgba_f32());
+        let end = Vec4::from(emitter.end_color.as_rgba_f32());
+        let materials = (0..GRADIENT_STEPS)
+            .map(|i| {
+                let color: Color = start
+                    .lerp(end, i as f32 / (GRADIENT_STEPS - 1) as f32)
+                    .into();
+                material_assets.add(StandardMaterial {
+                    base_color: color,
+                    alpha_mode: if color.a() < 1.0 {
+                        AlphaMode::Blend
+                    } else {
+                        AlphaMode::Opaque
+                    },
+                    unlit: true,
+                    ..default()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let particles = (0..emitter.max_particles)
+            .map(|_| {
+                commands
+                    .spawn((
+                        Name::new("Particle"),
+                        PbrBundle {
+                            mesh: mesh.0.clone(),
+                            material: materials[0].clone(),
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        NotShadowCaster,
+                        Particle {
+                            emitter: emitter_entity,
+                            alive: false,
+                            age: 0.0,
+                            velocity: Vec3::ZERO,
+                        },
+                    ))
+                    .id()
+            })
+            .collect();
+
+        commands.entity(emitter_entity).insert(ParticlePool {
+            particles,
+            next: 0,
+            materials,
+            rate_accumulator: 0.0,
+        });
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn emit_particles(
+    mut emitter_query: Query<(
+        Entity,
+        &ParticleEmitter,
+        &mut ParticlePool,
+        &GlobalTransform,
+    )>,
+    mut particle_query: Query<(
+        &mut Particle,
+        &mut Transform,
+        &mut Visibility,
+        &mut Handle<StandardMaterial>,
+    )>,
+    mut burst_reader: EventReader<ParticleBurst>,
+    time: Res<Time>,
+) {
+    let mut rng = rand::thread_rng();
+    let bursts = burst_reader.iter().collect::<Vec<_>>();
+
+    for (emitter_entity, emitter, mut pool, global_transform) in emitter_query.iter_mut() {
+        let mut spawns = Vec::new();
+        for burst in bursts
+            .iter()
+            .filter(|burst| burst.emitter == emitter_entity)
+        {
+            for _ in 0..emitter.burst {
+                spawns.push((burst.origin, burst.direction));
+            }
+        }
+
+        pool.rate_accumulator += emitter.rate * time.delta_seconds();
+        let (_, rotation, translation) = global_transform.to_scale_rotation_translation();
+        while pool.rate_accumulator >= 1.0 {
+            pool.rate_accumulator -= 1.0;
+            spawns.push((translation, rotation * emitter.direction));
+        }
+
+        for (origin, direction) in spawns {
+            let Some(entity) = pool.take() else {
+                break;
+            };
+            let Ok((mut particle, mut transform, mut visibility, mut material)) =
+                particle_query.get_mut(entity)
+            else {
+                continue;
+            };
+            particle.alive = true;
+            particle.age = 0.0;
+            particle.velocity = emitter.sample_velocity(direction, &mut rng);
+            *transform =
+                Transform::from_translation(origin).with_scale(Vec3::splat(emitter.start_size));
+            *visibility = Visibility::Inherited;
+            *material = pool.materials[0].clone();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn simulate_particles(
+    mut commands: Commands,
+    mut particle_query: Query<(
+        Entity,
+        &mut Particle,
+        &mut Transform,
+        &mut Visibility,
+        &mut Handle<StandardMaterial>,
+    )>,
+    emitter_query: Query<(&ParticleEmitter, &ParticlePool)>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (entity, mut particle, mut transform, mut visibility, mut material) in
+        particle_query.iter_mut()
+    {
+        let Ok((emitter, pool)) = emitter_query.get(particle.emitter) else {
+            // the pool goes away with its emitter
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+        if !particle.alive {
+            continue;
+        }
+
+        particle.age += dt;
+        if particle.age >= emitter.lifetime {
+            particle.alive = false;
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        particle.velocity += emitter.gravity * dt;
+        particle.velocity *= (-emitter.drag * dt).exp();
+        transform.translation += particle.velocity * dt;
+
+        let t = particle.age / emitter.lifetime;
+        transform.scale =
+            Vec3::splat(emitter.start_size + (emitter.end_size - emitter.start_size) * t);
+        let step = (t * (GRADIENT_STEPS - 1) as f32).round() as usize;
+        if let Some(step_material) = pool.materials.get(step) {
+            if *material != *step_material {
+                *material = step_material.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+/// Seconds between two simulation ticks
+pub const PHYSICS_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Runs rapier and all gameplay simulation in [`FixedUpdate`], so movement doesn't depend on
+/// the frame rate, and smooths the rendered position of bodies between ticks.
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FixedTime::new_from_secs(PHYSICS_TIMESTEP))
+            .init_resource::<SimulationClock>()
+            .insert_resource(RapierConfiguration {
+                timestep_mode: TimestepMode::Fixed {
+                    dt: PHYSICS_TIMESTEP,
+                    substeps: 1,
+                },
+                ..default()
+            })
+            .add_plugins(
+                RapierPhysicsPlugin::<NoUserData>::default().with_default_system_setup(false),
+            )
+            .configure_sets(
+                FixedUpdate,
+                (
+                    FixedStepSet::Prepare,
+                    FixedStepSet::Simulate,
+                    PhysicsSet::SyncBackend,
+                    PhysicsSet::SyncBackendFlush,
+                    PhysicsSet::StepSimulation,
+                    PhysicsSet::Writeback,
+                    FixedStepSet::Record,
+                )
+                    .chain(),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackend)
+                        .in_set(PhysicsSet::SyncBackend),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::SyncBackendFlush)
+                        .in_set(PhysicsSet::SyncBackendFlush),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::StepSimulation)
+                        .in_set(PhysicsSet::StepSimulation),
+                    RapierPhysicsPlugin::<NoUserData>::get_systems(PhysicsSet::Writeback)
+                        .in_set(PhysicsSet::Writeback),
+                    (advance_simulation_clock, restore_simulated_transforms)
+                        .in_set(FixedStepSet::Prepare),
+                    record_simulated_transforms.in_set(FixedStepSet::Record),
+                ),
+            )
+            .add_systems(
+                Update,
+                interpolate_rendered_transforms.in_set(RenderInterpolationSet),
+            );
+    }
+}
+
+/// Ordering of the [`FixedUpdate`] schedule around the rapier step
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FixedStepSet {
+    /// Input snapshots and transform restoring
+    Prepare,
+    /// Gameplay that moves bodies before the physics step
+    Simulate,
+    /// Reading back the result of the physics step
+    Record,
+}
+
+/// Number of fixed ticks simulated so far. Timing that levels are designed around should be
+/// based on this rather than on frame time
+#[derive(Resource, Default)]
+pub struct SimulationClock {
+    ticks: u64,
+    level_start: u64,
+}
+
+impl SimulationClock {
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    pub fn elapsed_seconds(&self) -> f32 {
+        // computed from the tick count so it never drifts from summing up timesteps
+        (self.ticks as f64 * PHYSICS_TIMESTEP as f64) as f32
+    }
+
+    /// Ticks since the current level was loaded or restarted, so its timing is the same on
+    /// every attempt
+    pub fn level_ticks(&self) -> u64 {
+        self.ticks - self.level_start
+    }
+
+    /// Starts [`SimulationClock::level_ticks`] over
+    pub fn restart_level(&mut self) {
+        self.level_start = self.ticks;
+    }
+}
+
+/// Systems in [`Update`] that read simulated transforms should run after this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RenderInterpolationSet;
+
+/// Renders a body simulated in [`FixedUpdate`] in between its last two ticks.
+///
+/// Changing the [`Transform`] outside of [`FixedUpdate`] teleports the body without
+/// interpolating.
+#[derive(Component, Default)]
+pub struct RenderInterpolation {
+    previous: Option<Transform>,
+    current: Option<Transform>,
+    rendered: Option<Transform>,
+}
+
+fn advance_simulation_clock(mut clock: ResMut<SimulationClock>) {
+    clock.ticks += 1;
+}
+
+fn restore_simulated_transforms(mut query: Query<(&mut Transform, &mut RenderInterpolation)>) {
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        let Some(current) = interpolation.current else {
+            continue;
+        };
+        if interpolation.rendered.unwrap_or(current) == *transform {
+            *transform = current;
+        } else {
+            interpolation.previous = None;
+            interpolation.current = None;
+        }
+    }
+}
+
+fn record_simulated_transforms(mut query: Query<(&Transform, &mut RenderInterpolation)>) {
+    for (transform, mut interpolation) in query.iter_mut() {
+        interpolation.previous = Some(interpolation.current.unwrap_or(*transform));
+        interpolation.current = Some(*transform);
+        interpolation.rendered = None;
+    }
+}
+
+fn interpolate_rendered_transforms(
+    mut query: Query<(&mut Transform, &mut RenderInterpolation)>,
+    fixed_time: Res<FixedTime>,
+) {
+    let alpha =
+        (fixed_time.accumulated().as_secs_f32() / fixed_time.period.as_secs_f32()).clamp(0.0, 1.0);
+
+    for (mut transform, mut interpolation) in query.iter_mut() {
+        let (Some(previous), Some(current)) = (interpolation.previous, interpolation.current)
+        else {
+            continue;
+        };
+        // a transform that changed since the last tick was moved by something else
+        if interpolation.rendered.unwrap_or(current) != *transform {
+            continue;
+        }
+        transform.translation = previous.translation.lerp(current.translation, alpha);
+        transform.rotation = previous.rotation.slerp(current.rotation, alpha);
+        transform.scale = previous.scale.lerp(current.scale, alpha);
+        interpolation.rendered = Some(*transform);
+    }
+}
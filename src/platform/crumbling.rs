@@ -0,0 +1,281 @@
+use crate::lifetime::Lifetime;
+use crate::physics::{FixedStepSet, SimulationClock, PHYSICS_TIMESTEP};
+use crate::player::{GroundSensorSet, Grounded, Player};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How far a platform about to give way shakes
+const SHAKE_AMPLITUDE: f32 = 0.06;
+/// Seconds a fallen platform's debris stays around
+const DEBRIS_LIFETIME: f32 = 3.0;
+
+pub struct CrumblingPlugin;
+
+impl Plugin for CrumblingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                split_platform_visuals,
+                apply_deferred,
+                crumble_platforms,
+                toggle_timed_platforms,
+            )
+                .chain()
+                .after(GroundSensorSet)
+                .in_set(FixedStepSet::Simulate),
+        );
+    }
+}
+
+/// Platform that shakes once a player stands on it, then gives way and comes back later
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrumblingPlatform {
+    /// Seconds of shaking between being stepped on and giving way
+    pub delay: f32,
+    /// Seconds until it comes back
+    pub respawn_time: f32,
+    /// Whether it drops as debris rather than vanishing
+    pub fall: bool,
+}
+
+impl Default for CrumblingPlatform {
+    fn default() -> Self {
+        Self {
+            delay: 0.75,
+            respawn_time: 3.0,
+            fall: true,
+        }
+    }
+}
+
+/// Platform that is solid for part of a repeating cycle, in step with every other one
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimedPlatform {
+    /// Seconds of a whole on and off cycle
+    pub period: f32,
+    /// Seconds of each cycle during which it is solid, starting at the beginning of the cycle
+    pub solid_time: f32,
+    /// Seconds the cycle is shifted by, so neighbouring platforms can take turns
+    pub offset: f32,
+    /// Seconds of shaking before it vanishes
+    pub warning: f32,
+}
+
+impl Default for TimedPlatform {
+    fn default() -> Self {
+        Self {
+            period: 4.0,
+            solid_time: 2.0,
+            offset: 0.0,
+            warning: 0.5,
+        }
+    }
+}
+
+fn to_ticks(seconds: f32) -> u64 {
+    (seconds / PHYSICS_TIMESTEP).round().max(0.0) as u64
+}
+
+/// The rendered part of a crumbling or timed platform, moved into a child so it can shake
+/// without moving the collider
+#[derive(Component)]
+struct PlatformVisual {
+    visual: Entity,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Component, Default)]
+enum CrumblePhase {
+    #[default]
+    Solid,
+    Shaking {
+        ticks_left: u64,
+    },
+    Gone {
+        ticks_left: u64,
+    },
+}
+
+#[allow(clippy::type_complexity)]
+fn split_platform_visuals(
+    mut commands: Commands,
+    platform_query: Query<
+        (
+            Entity,
+            &Handle<Mesh>,
+            &Handle<StandardMaterial>,
+            Option<&CrumblingPlatform>,
+        ),
+        (
+            Or<(With<CrumblingPlatform>, With<TimedPlatform>)>,
+            Without<PlatformVisual>,
+        ),
+    >,
+) {
+    for (entity, mesh, material, crumbling) in platform_query.iter() {
+        let visual = commands
+            .spawn(PbrBundle {
+                mesh: mesh.clone(),
+                material: material.clone(),
+                ..default()
+            })
+            .id();
+        let mut platform = commands.entity(entity);
+        platform
+            .remove::<(Handle<Mesh>, Handle<StandardMaterial>)>()
+            .insert(PlatformVisual {
+                visual,
+                mesh: mesh.clone(),
+                material: material.clone(),
+            })
+            .add_child(visual);
+        if crumbling.is_some() {
+            platform.insert(CrumblePhase::default());
+        }
+    }
+}
+
+fn shake_offset(clock: &SimulationClock) -> Vec3 {
+    let t = clock.elapsed_seconds();
+    Vec3::new((t * 71.0).sin(), (t * 53.0).sin() * 0.5, (t * 89.0).cos()) * SHAKE_AMPLITUDE
+}
+
+fn set_solid(commands: &mut Commands, entity: Entity, solid: bool) {
+    let mut platform = commands.entity(entity);
+    if solid {
+        platform
+            .remove::<ColliderDisabled>()
+            .insert(Visibility::Inherited);
+    } else {
+        platform.insert((ColliderDisabled, Visibility::Hidden));
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn crumble_platforms(
+    mut commands: Commands,
+    mut platform_query: Query<(
+        Entity,
+        &CrumblingPlatform,
+        &mut CrumblePhase,
+        &PlatformVisual,
+        &GlobalTransform,
+        &Collider,
+    )>,
+    mut visual_query: Query<&mut Transform>,
+    player_query: Query<&Grounded, With<Player>>,
+    parent_query: Query<&Parent>,
+    rapier_ctx: Res<RapierContext>,
+    clock: Res<SimulationClock>,
+) {
+    // the ground may be a collider somewhere below the platform
+    let stood_on = player_query
+        .iter()
+        .flat_map(|grounded| {
+            std::iter::once(grounded.entity).chain(parent_query.iter_ancestors(grounded.entity))
+        })
+        .collect::<Vec<_>>();
+
+    for (entity, crumbling, mut phase, visual, transform, collider) in platform_query.iter_mut() {
+        match &mut *phase {
+            CrumblePhase::Solid => {
+                if stood_on.contains(&entity) {
+                    *phase = CrumblePhase::Shaking {
+                        ticks_left: to_ticks(crumbling.delay),
+                    };
+                }
+            }
+            CrumblePhase::Shaking { ticks_left } => {
+                if let Ok(mut visual_transform) = visual_query.get_mut(visual.visual) {
+                    visual_transform.translation = shake_offset(&clock);
+                }
+                *ticks_left = ticks_left.saturating_sub(1);
+                if *ticks_left > 0 {
+                    continue;
+                }
+
+                if let Ok(mut visual_transform) = visual_query.get_mut(visual.visual) {
+                    visual_transform.translation = Vec3::ZERO;
+                }
+                if crumbling.fall {
+                    commands.spawn((
+                        Name::new("Platform Debris"),
+                        PbrBundle {
+                            mesh: visual.mesh.clone(),
+                            material: visual.material.clone(),
+                            transform: transform.compute_transform(),
+                            ..default()
+                        },
+                        RigidBody::Dynamic,
+                        collider.clone(),
+                        Lifetime::from_seconds(DEBRIS_LIFETIME),
+                    ));
+                }
+                set_solid(&mut commands, entity, false);
+                *phase = CrumblePhase::Gone {
+                    ticks_left: to_ticks(crumbling.respawn_time),
+                };
+            }
+            CrumblePhase::Gone { ticks_left } => {
+                *ticks_left = ticks_left.saturating_sub(1);
+                if *ticks_left > 0 {
+                    continue;
+                }
+
+                // wait for the spot to be clear rather than coming back inside someone
+                let (_, rotation, translation) = transform.to_scale_rotation_translation();
+                let blocked = rapier_ctx
+                    .intersection_with_shape(
+                        translation,
+                        rotation,
+                        collider,
+                        QueryFilter::exclude_fixed().exclude_sensors(),
+                    )
+                    .is_some();
+                if blocked {
+                    continue;
+                }
+                set_solid(&mut commands, entity, true);
+                *phase = CrumblePhase::Solid;
+            }
+        }
+    }
+}
+
+fn toggle_timed_platforms(
+    mut commands: Commands,
+    platform_query: Query<(
+        Entity,
+        &TimedPlatform,
+        &PlatformVisual,
+        Option<&ColliderDisabled>,
+    )>,
+    mut visual_query: Query<&mut Transform>,
+    clock: Res<SimulationClock>,
+) {
+    for (entity, timed, visual, disabled) in platform_query.iter() {
+        // whole ticks keep every platform on the same beat, which starts with the level like
+        // moving platforms do
+        let period = to_ticks(timed.period).max(1);
+        let tick_in_cycle = (clock.level_ticks() + to_ticks(timed.offset)) % period;
+        let solid_ticks = to_ticks(timed.solid_time);
+        let solid = tick_in_cycle < solid_ticks;
+        let warning = solid && tick_in_cycle + to_ticks(timed.warning) >= solid_ticks;
+
+        if let Ok(mut visual_transform) = visual_query.get_mut(visual.visual) {
+            visual_transform.translation = if warning {
+                shake_offset(&clock)
+            } else {
+                Vec3::ZERO
+            };
+        }
+        if solid == disabled.is_some() {
+            set_solid(&mut commands, entity, solid);
+        }
+    }
+}
@@ -0,0 +1,413 @@
+mod crumbling;
+
+use crate::physics::FixedStepSet;
+use crate::player::{CharacterMotorSet, CharacterMotorState, GroundSensorSet, Grounded};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use crumbling::CrumblingPlugin;
+use serde::{Deserialize, Serialize};
+
+pub use crumbling::{CrumblingPlatform, TimedPlatform};
+
+pub struct PlatformPlugin;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(CrumblingPlugin).add_systems(
+            FixedUpdate,
+            (
+                start_moving_platforms,
+                apply_deferred,
+                move_platforms,
+                carry_dynamic_bodies,
+                carry_platform_riders
+                    .after(GroundSensorSet)
+                    .before(CharacterMotorSet),
+            )
+                .chain()
+                .in_set(FixedStepSet::Simulate),
+        );
+    }
+}
+
+/// How a value moves between two keyframes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Maps progress `t` in `0..=1` to eased progress
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// What happens after the last keyframe
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlatformRepeat {
+    /// Stay at the last keyframe
+    Once,
+    /// Jump back to the first keyframe. Repeat the first keyframe at the end for a closed path
+    Loop,
+    /// Go back through the keyframes in reverse
+    #[default]
+    PingPong,
+}
+
+/// Timing shared by every keyframe of a platform motion
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlatformTiming {
+    /// Seconds spent moving from one keyframe to the next
+    pub duration: f32,
+    /// Seconds spent still on each keyframe before moving on
+    pub wait: f32,
+    pub easing: Easing,
+    pub repeat: PlatformRepeat,
+}
+
+impl Default for PlatformTiming {
+    fn default() -> Self {
+        Self {
+            duration: 2.0,
+            wait: 0.5,
+            easing: Easing::default(),
+            repeat: PlatformRepeat::default(),
+        }
+    }
+}
+
+impl PlatformTiming {
+    /// The keyframes moved between at `elapsed` seconds, and the eased progress from one to
+    /// the other
+    fn sample(&self, elapsed: f32, keyframes: usize) -> (usize, usize, f32) {
+        let step_time = self.wait + self.duration;
+        if keyframes < 2 || step_time <= 0.0 {
+            return (0, 0, 0.0);
+        }
+        let last = keyframes - 1;
+        let steps = match self.repeat {
+            PlatformRepeat::PingPong => last * 2,
+            PlatformRepeat::Once | PlatformRepeat::Loop => last,
+        };
+
+        let step = (elapsed / step_time) as usize;
+        if self.repeat == PlatformRepeat::Once && step >= steps {
+            return (last, last, 0.0);
+        }
+        let time_in_step = elapsed - step as f32 * step_time;
+        let step = step % steps;
+
+        let t = if self.duration > 0.0 {
+            ((time_in_step - self.wait) / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        let t = self.easing.apply(t);
+
+        if step < last {
+            (step, step + 1, t)
+        } else {
+            // on the way back of a ping-pong
+            let from = last * 2 - step;
+            (from, from - 1, t)
+        }
+    }
+}
+
+/// Offsets from the platform's starting position, in its parent's space
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlatformPath {
+    pub waypoints: Vec<Vec3>,
+    pub timing: PlatformTiming,
+}
+
+/// Angles in degrees around an axis in the platform's local space
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlatformRotation {
+    pub axis: Vec3,
+    pub angles: Vec<f32>,
+    pub timing: PlatformTiming,
+}
+
+impl Default for PlatformRotation {
+    fn default() -> Self {
+        Self {
+            axis: Vec3::Y,
+            angles: Vec::new(),
+            timing: PlatformTiming::default(),
+        }
+    }
+}
+
+/// Moves a collider along a path and/or rotation curve, relative to where it started.
+///
+/// Characters grounded on it are carried along by `carry_platform_riders`, and dynamic bodies
+/// resting on it by `carry_dynamic_bodies`. The collider must not get a kinematic body, since
+/// rapier's character controller would also drag characters along with the body's velocity,
+/// which lags a tick behind and flings them off rotating platforms.
+#[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MovingPlatform {
+    pub path: Option<PlatformPath>,
+    pub rotation: Option<PlatformRotation>,
+}
+
+impl MovingPlatform {
+    fn transform_at(&self, origin: &Transform, elapsed: f32) -> Transform {
+        let mut transform = *origin;
+        if let Some(path) = &self.path {
+            if !path.waypoints.is_empty() {
+                let (from, to, t) = path.timing.sample(elapsed, path.waypoints.len());
+                transform.translation += path.waypoints[from].lerp(path.waypoints[to], t);
+            }
+        }
+        if let Some(rotation) = &self.rotation {
+            if !rotation.angles.is_empty() {
+                let (from, to, t) = rotation.timing.sample(elapsed, rotation.angles.len());
+                let angle =
+                    rotation.angles[from] + (rotation.angles[to] - rotation.angles[from]) * t;
+                transform.rotation *=
+                    Quat::from_axis_angle(rotation.axis.normalize_or_zero(), angle.to_radians());
+            }
+        }
+        transform
+    }
+}
+
+#[derive(Component)]
+struct PlatformMotion {
+    origin: Transform,
+    elapsed: f32,
+    /// World space pose before and after the last tick
+    previous: Transform,
+    current: Transform,
+}
+
+/// Present on a character while it is carried by a [`MovingPlatform`]
+#[derive(Component)]
+pub struct PlatformRider {
+    pub platform: Entity,
+    /// Velocity of the platform under the character, kept when it leaves the platform
+    pub velocity: Vec3,
+}
+
+fn world_transform(parent: Option<&GlobalTransform>, local: Transform) -> Transform {
+    match parent {
+        Some(parent) => parent.mul_transform(local).compute_transform(),
+        None => local,
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn start_moving_platforms(
+    mut commands: Commands,
+    platform_query: Query<
+        (Entity, &Transform, Option<&Parent>),
+        (With<MovingPlatform>, Without<PlatformMotion>),
+    >,
+    parent_query: Query<&GlobalTransform>,
+) {
+    for (entity, transform, parent) in platform_query.iter() {
+        let parent = parent.and_then(|parent| parent_query.get(parent.get()).ok());
+        let world = world_transform(parent, *transform);
+        commands.entity(entity).insert(PlatformMotion {
+            origin: *transform,
+            elapsed: 0.0,
+            previous: world,
+            current: world,
+        });
+    }
+}
+
+fn move_platforms(
+    mut platform_query: Query<(
+        &MovingPlatform,
+        &mut PlatformMotion,
+        &mut Transform,
+        Option<&Parent>,
+    )>,
+    parent_query: Query<&GlobalTransform>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (platform, mut motion, mut transform, parent) in platform_query.iter_mut() {
+        motion.elapsed += dt;
+        *transform = platform.transform_at(&motion.origin, motion.elapsed);
+
+        let parent = parent.and_then(|parent| parent_query.get(parent.get()).ok());
+        motion.previous = motion.current;
+        motion.current = world_transform(parent, *transform);
+    }
+}
+
+/// Rapier sees a platform as a still collider, so dynamic bodies on it are moved with it by hand
+fn carry_dynamic_bodies(
+    mut body_query: Query<(Entity, &RigidBody, &Collider, &mut Transform), Without<PlatformMotion>>,
+    motion_query: Query<&PlatformMotion>,
+    parent_query: Query<&Parent>,
+    rapier_ctx: Res<RapierContext>,
+) {
+    for (entity, body, collider, mut transform) in body_query.iter_mut() {
+        if *body != RigidBody::Dynamic {
+            continue;
+        }
+        // a little past the bottom of the body, whichever way it is turned
+        let reach = collider.raw.compute_local_bounding_sphere().radius + 0.1;
+        let Some((ground, _)) = rapier_ctx.cast_ray(
+            transform.translation,
+            Vec3::NEG_Y,
+            reach,
+            true,
+            QueryFilter::new()
+                .exclude_rigid_body(entity)
+                .exclude_sensors(),
+        ) else {
+            continue;
+        };
+        let Some(motion) = std::iter::once(ground)
+            .chain(parent_query.iter_ancestors(ground))
+            .find_map(|entity| motion_query.get(entity).ok())
+        else {
+            continue;
+        };
+
+        let delta_rotation = motion.current.rotation * motion.previous.rotation.inverse();
+        transform.translation = motion.current.translation
+            + delta_rotation * (transform.translation - motion.previous.translation);
+        transform.rotation = delta_rotation * transform.rotation;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn carry_platform_riders(
+    mut commands: Commands,
+    mut rider_query: Query<(
+        Entity,
+        &mut Transform,
+        &mut CharacterMotorState,
+        Option<&Grounded>,
+        Option<&PlatformRider>,
+    )>,
+    motion_query: Query<&PlatformMotion>,
+    parent_query: Query<&Parent>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (rider, mut transform, mut motor, grounded, riding) in rider_query.iter_mut() {
+        // the ground may be a collider somewhere below the platform's body
+        let platform = grounded.and_then(|grounded| {
+            std::iter::once(grounded.entity)
+                .chain(parent_query.iter_ancestors(grounded.entity))
+                .find(|&entity| motion_query.contains(entity))
+        });
+
+        let Some(platform) = platform else {
+            // keep the platform's momentum when jumping or walking off
+            if let Some(riding) = riding {
+                motor.apply_impulse(riding.velocity);
+                commands.entity(rider).remove::<PlatformRider>();
+            }
+            continue;
+        };
+        let motion = motion_query.get(platform).unwrap();
+
+        // the controller moves from where the rider was, against where the platform was, so
+        // moving the rider by the platform's displacement keeps the two in contact
+        let delta_rotation = motion.current.rotation * motion.previous.rotation.inverse();
+        let carried = motion.current.translation
+            + delta_rotation * (transform.translation - motion.previous.translation);
+        let velocity = (carried - transform.translation) / dt;
+        transform.translation = carried;
+        let (delta_yaw, _, _) = delta_rotation.to_euler(EulerRot::YXZ);
+        transform.rotate_y(delta_yaw);
+
+        commands
+            .entity(rider)
+            .insert(PlatformRider { platform, velocity });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PhysicsPlugin;
+    use bevy::{scene::SceneSpawner, time::TimeUpdateStrategy};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        .init_resource::<SceneSpawner>()
+        // ticks are run by hand, so frames only propagate transforms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(default()))
+        .add_plugins((PhysicsPlugin, PlatformPlugin));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn dynamic_bodies_ride_moving_platforms() {
+        let mut app = app();
+        let platform = app
+            .world
+            .spawn((
+                TransformBundle::default(),
+                Collider::cuboid(2.0, 0.25, 2.0),
+                MovingPlatform {
+                    path: Some(PlatformPath {
+                        waypoints: vec![Vec3::ZERO, Vec3::new(6.0, 1.0, 0.0)],
+                        timing: PlatformTiming {
+                            duration: 2.0,
+                            wait: 0.0,
+                            easing: Easing::Linear,
+                            repeat: PlatformRepeat::Once,
+                        },
+                    }),
+                    rotation: None,
+                },
+            ))
+            .id();
+        let body = app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, 0.5, 0.0)),
+                RigidBody::Dynamic,
+                Collider::cuboid(0.25, 0.25, 0.25),
+            ))
+            .id();
+
+        for _ in 0..120 {
+            app.world.run_schedule(FixedUpdate);
+            app.update();
+        }
+
+        let platform = app.world.get::<Transform>(platform).unwrap().translation;
+        let body = app.world.get::<Transform>(body).unwrap().translation;
+        assert!(platform.abs_diff_eq(Vec3::new(6.0, 1.0, 0.0), 1e-3));
+        assert!(
+            (body.x - platform.x).abs() < 0.5 && (body.y - platform.y - 0.5).abs() < 0.1,
+            "left behind at {body}"
+        );
+    }
+}
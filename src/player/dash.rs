@@ -0,0 +1,186 @@
+use crate::physics::FixedStepSet;
+use crate::player::{
+    CharacterMotorSet, CharacterMotorState, GroundSensorSet, Grounded, PlayerControlSet,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct DashPlugin;
+
+impl Plugin for DashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DashStarted>()
+            .add_event::<DashEnded>()
+            .add_systems(
+                FixedUpdate,
+                apply_dash
+                    .in_set(PlayerControlSet)
+                    .in_set(FixedStepSet::Simulate)
+                    .after(GroundSensorSet)
+                    .before(CharacterMotorSet),
+            );
+    }
+}
+
+/// Dash tuning for a body with a [`CharacterMotor`](crate::player::CharacterMotor)
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Dash {
+    /// Speed kept for the whole dash
+    pub speed: f32,
+    /// Seconds the dash lasts. Gravity and damping are ignored meanwhile
+    pub duration: f32,
+    /// Seconds after a dash ends before the next one can start
+    pub cooldown: f32,
+    /// Dashes available before touching the ground again
+    pub max_charges: u32,
+    /// Dashes allowed per airborne stretch, which also need a charge left. `None` to only be
+    /// limited by charges
+    pub max_air_dashes: Option<u32>,
+}
+
+/// The player's tuning, which fields missing from `player.stats.ron` fall back to
+impl Default for Dash {
+    fn default() -> Self {
+        Self {
+            speed: 32.0,
+            duration: 0.15,
+            cooldown: 0.5,
+            max_charges: 1,
+            max_air_dashes: Some(1),
+        }
+    }
+}
+
+#[derive(Default)]
+enum DashPhase {
+    #[default]
+    Ready,
+    Dashing {
+        remaining: f32,
+        direction: Vec3,
+    },
+}
+
+/// Per-entity dash input, charges and cooldown.
+///
+/// The player's input is written by `player_movement`. Anything else with a [`Dash`] can set
+/// `requested_direction` directly.
+#[derive(Component, Default)]
+pub struct DashState {
+    /// Direction to dash in this frame, cleared once read
+    pub requested_direction: Option<Vec3>,
+    /// Prevents starting a dash this frame, e.g. on sticky ground. Cleared once read
+    pub blocked: bool,
+    charges: u32,
+    air_dashes: u32,
+    cooldown_timer: f32,
+    phase: DashPhase,
+}
+
+impl DashState {
+    pub fn is_dashing(&self) -> bool {
+        matches!(self.phase, DashPhase::Dashing { .. })
+    }
+
+    pub fn charges(&self) -> u32 {
+        self.charges
+    }
+
+    /// Seconds until the cooldown allows another dash
+    pub fn cooldown_remaining(&self) -> f32 {
+        self.cooldown_timer
+    }
+
+    fn can_dash(&self, dash: &Dash, grounded: bool) -> bool {
+        !self.is_dashing()
+            && self.charges > 0
+            && self.cooldown_timer <= 0.0
+            && (grounded || !matches!(dash.max_air_dashes, Some(max) if self.air_dashes >= max))
+    }
+}
+
+#[derive(Event)]
+pub struct DashStarted {
+    pub entity: Entity,
+    pub start_position: Vec3,
+    pub direction: Vec3,
+}
+
+#[derive(Event)]
+pub struct DashEnded {
+    pub entity: Entity,
+    pub end_position: Vec3,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_dash(
+    mut dash_query: Query<(
+        Entity,
+        &Transform,
+        &Dash,
+        &mut DashState,
+        &mut CharacterMotorState,
+        Option<&Grounded>,
+    )>,
+    fixed_time: Res<FixedTime>,
+    mut started_writer: EventWriter<DashStarted>,
+    mut ended_writer: EventWriter<DashEnded>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (entity, transform, dash, mut state, mut motor, grounded) in dash_query.iter_mut() {
+        state.cooldown_timer = (state.cooldown_timer - dt).max(0.0);
+        let requested_direction = state.requested_direction.take();
+        let blocked = std::mem::take(&mut state.blocked);
+
+        if let DashPhase::Dashing {
+            remaining,
+            direction,
+        } = &mut state.phase
+        {
+            motor.velocity = *direction * dash.speed;
+            *remaining -= dt;
+            if *remaining > 0.0 {
+                continue;
+            }
+
+            motor.hold_velocity = false;
+            state.phase = DashPhase::Ready;
+            state.cooldown_timer = dash.cooldown;
+            ended_writer.send(DashEnded {
+                entity,
+                end_position: transform.translation,
+            });
+            continue;
+        }
+
+        if grounded.is_some() {
+            state.charges = dash.max_charges;
+            state.air_dashes = 0;
+        }
+
+        let Some(direction) = requested_direction.and_then(Vec3::try_normalize) else {
+            continue;
+        };
+        if blocked || !state.can_dash(dash, grounded.is_some()) {
+            continue;
+        }
+
+        state.charges -= 1;
+        if grounded.is_none() {
+            state.air_dashes += 1;
+        }
+        state.phase = DashPhase::Dashing {
+            remaining: dash.duration,
+            direction,
+        };
+        motor.hold_velocity = true;
+        motor.velocity = direction * dash.speed;
+        started_writer.send(DashStarted {
+            entity,
+            start_position: transform.translation,
+            direction,
+        });
+    }
+}
@@ -0,0 +1,192 @@
+use crate::physics::FixedStepSet;
+use crate::player::CharacterMotorState;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+pub struct GroundPlugin;
+
+impl Plugin for GroundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Landed>()
+            .add_event::<LeftGround>()
+            .add_systems(
+                FixedUpdate,
+                update_ground_sensors
+                    .in_set(GroundSensorSet)
+                    .in_set(FixedStepSet::Simulate),
+            );
+    }
+}
+
+/// Systems that need an up to date [`Grounded`] should run after this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GroundSensorSet;
+
+/// Casts a box down from the entity every frame and keeps [`Grounded`] in sync with the result
+#[derive(Component)]
+pub struct GroundSensor {
+    /// Half extents of the box that is cast
+    pub half_extents: Vec3,
+    /// How far below the entity the ground is looked for
+    pub max_distance: f32,
+    /// Steepest surface, in radians, that still counts as ground
+    pub max_slope_angle: f32,
+}
+
+impl Default for GroundSensor {
+    fn default() -> Self {
+        Self {
+            half_extents: Vec3::splat(0.5),
+            max_distance: 0.5,
+            max_slope_angle: 45.0_f32.to_radians(),
+        }
+    }
+}
+
+/// Present while a [`GroundSensor`] is standing on something
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Grounded {
+    /// World space normal of the ground surface
+    pub normal: Vec3,
+    /// The entity of the ground collider
+    pub entity: Entity,
+    /// Distance between the bottom of the sensor and the ground
+    pub distance: f32,
+}
+
+#[derive(Event)]
+pub struct Landed {
+    pub entity: Entity,
+    pub ground: Entity,
+    /// Velocity of the entity on the frame it touched the ground
+    pub impact_velocity: Vec3,
+}
+
+#[derive(Event)]
+pub struct LeftGround {
+    pub entity: Entity,
+}
+
+#[allow(clippy::type_complexity)]
+pub fn update_ground_sensors(
+    mut commands: Commands,
+    sensor_query: Query<(
+        Entity,
+        &Transform,
+        &GroundSensor,
+        Option<&Grounded>,
+        Option<&CharacterMotorState>,
+    )>,
+    rapier_ctx: Res<RapierContext>,
+    mut landed_writer: EventWriter<Landed>,
+    mut left_ground_writer: EventWriter<LeftGround>,
+) {
+    for (entity, transform, sensor, grounded, motor) in sensor_query.iter() {
+        let hit = rapier_ctx.cast_shape(
+            transform.translation,
+            Quat::IDENTITY,
+            Vec3::NEG_Y,
+            &Collider::cuboid(
+                sensor.half_extents.x,
+                sensor.half_extents.y,
+                sensor.half_extents.z,
+            ),
+            sensor.max_distance,
+            // trigger volumes and the like are no ground to stand on
+            QueryFilter::new()
+                .exclude_rigid_body(entity)
+                .exclude_sensors(),
+        );
+
+        let ground = hit.and_then(|(ground_entity, toi)| {
+            // the normal is undefined when the cast starts inside the ground
+            let normal = if toi.status == TOIStatus::Penetrating {
+                Vec3::Y
+            } else {
+                toi.normal1
+            };
+            (normal.angle_between(Vec3::Y) <= sensor.max_slope_angle).then_some(Grounded {
+                normal,
+                entity: ground_entity,
+                distance: toi.toi,
+            })
+        });
+
+        match (ground, grounded) {
+            (Some(ground), previous) => {
+                if previous.is_none() {
+                    landed_writer.send(Landed {
+                        entity,
+                        ground: ground.entity,
+                        impact_velocity: motor.map_or(Vec3::ZERO, |motor| motor.velocity),
+                    });
+                }
+                commands.entity(entity).insert(ground);
+            }
+            (None, Some(_)) => {
+                left_ground_writer.send(LeftGround { entity });
+                commands.entity(entity).remove::<Grounded>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PhysicsPlugin;
+    use bevy::{scene::SceneSpawner, time::TimeUpdateStrategy};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        .init_resource::<SceneSpawner>()
+        // ticks are run by hand, so frames only propagate transforms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(default()))
+        .add_plugins((PhysicsPlugin, GroundPlugin));
+        app.update();
+        app
+    }
+
+    /// Whether a body resting on top of `ground` is grounded
+    fn grounded_on(ground: impl Bundle) -> bool {
+        let mut app = app();
+        app.world.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
+            Collider::cuboid(2.0, 0.5, 2.0),
+            ground,
+        ));
+        let body = app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, 0.5, 0.0)),
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(0.5, 0.5, 0.5),
+                GroundSensor {
+                    half_extents: Vec3::splat(0.45),
+                    max_distance: 0.15,
+                    ..default()
+                },
+            ))
+            .id();
+        for _ in 0..2 {
+            app.world.run_schedule(FixedUpdate);
+            app.update();
+        }
+        app.world.get::<Grounded>(body).is_some()
+    }
+
+    #[test]
+    fn sensors_are_not_ground() {
+        assert!(grounded_on(RigidBody::Fixed));
+        assert!(!grounded_on(Sensor));
+    }
+}
@@ -0,0 +1,326 @@
+use crate::actions::{Action, FixedActionState};
+use crate::physics::FixedStepSet;
+use crate::player::{
+    CharacterMotorSet, CharacterMotorState, GroundSensorSet, Grounded, Player, PlayerControlSet,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct JumpPlugin;
+
+impl Plugin for JumpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (read_jump_input, apply_jump)
+                .chain()
+                .in_set(PlayerControlSet)
+                .in_set(FixedStepSet::Simulate)
+                .after(GroundSensorSet)
+                .before(CharacterMotorSet),
+        );
+    }
+}
+
+/// Jump tuning for a body with a [`CharacterMotor`](crate::player::CharacterMotor)
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Jump {
+    /// Upward speed given on takeoff
+    pub jump_power: f32,
+    /// Seconds after walking off a ledge during which a jump is still allowed
+    pub coyote_time: f32,
+    /// Seconds a jump press is remembered while airborne, so it fires on landing
+    pub buffer_time: f32,
+    /// Upward speed is multiplied by this when jump is released early.
+    /// `1.0` disables variable jump height
+    pub jump_cut: f32,
+}
+
+impl Jump {
+    pub fn from_jump_power(jump_power: f32) -> Self {
+        Self {
+            jump_power,
+            ..default()
+        }
+    }
+}
+
+/// The player's tuning, which fields missing from `player.stats.ron` fall back to
+impl Default for Jump {
+    fn default() -> Self {
+        Self {
+            jump_power: 32.0,
+            coyote_time: 0.1,
+            buffer_time: 0.15,
+            jump_cut: 0.5,
+        }
+    }
+}
+
+/// Per-entity jump input and timers.
+///
+/// The player's input is written by [`read_jump_input`]. Anything else with a [`Jump`] can set
+/// `jump_pressed`/`jump_held` directly.
+#[derive(Component, Default)]
+pub struct JumpState {
+    /// Jump was pressed this frame
+    pub jump_pressed: bool,
+    /// Jump is being held down
+    pub jump_held: bool,
+    coyote_timer: f32,
+    buffer_timer: f32,
+    is_jumping: bool,
+    /// Jump was held at takeoff and hasn't been released since, so releasing it cuts the jump
+    cuttable: bool,
+}
+
+impl JumpState {
+    /// Whether the body is still rising from a jump
+    pub fn is_jumping(&self) -> bool {
+        self.is_jumping
+    }
+}
+
+pub fn read_jump_input(
+    mut player_query: Query<&mut JumpState, With<Player>>,
+    actions: Res<FixedActionState>,
+) {
+    for mut state in player_query.iter_mut() {
+        state.jump_pressed = actions.just_pressed(Action::Jump);
+        state.jump_held = actions.pressed(Action::Jump);
+    }
+}
+
+pub fn apply_jump(
+    mut jump_query: Query<(
+        &Jump,
+        &mut JumpState,
+        &mut CharacterMotorState,
+        Option<&Grounded>,
+    )>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (jump, mut state, mut motor, grounded) in jump_query.iter_mut() {
+        // only refresh coyote time once we stop rising, otherwise the frames right after
+        // takeoff would count as grounded and allow a second jump
+        if !state.is_jumping && grounded.is_some() {
+            state.coyote_timer = jump.coyote_time;
+        } else {
+            state.coyote_timer -= dt;
+        }
+
+        if state.jump_pressed {
+            state.buffer_timer = jump.buffer_time;
+        }
+
+        let wants_jump = state.jump_pressed || state.buffer_timer > 0.0;
+        if wants_jump && state.coyote_timer > 0.0 {
+            motor.velocity.y = jump.jump_power;
+            state.is_jumping = true;
+            // a buffered tap was released before takeoff, and still gets a full jump
+            state.cuttable = state.jump_held;
+            state.coyote_timer = 0.0;
+            state.buffer_timer = 0.0;
+        } else {
+            state.buffer_timer -= dt;
+        }
+
+        if state.is_jumping && state.cuttable && !state.jump_held && motor.velocity.y > 0.0 {
+            motor.velocity.y *= jump.jump_cut;
+            state.cuttable = false;
+        }
+        if motor.velocity.y <= 0.0 {
+            state.is_jumping = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{PhysicsPlugin, PHYSICS_TIMESTEP};
+    use crate::player::ground::{GroundPlugin, GroundSensor};
+    use crate::player::motor::MotorPlugin;
+    use crate::player::PlayerStats;
+    use bevy::{scene::SceneSpawner, time::TimeUpdateStrategy};
+    use bevy_rapier3d::prelude::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        .init_resource::<SceneSpawner>()
+        .init_resource::<FixedActionState>()
+        // ticks are run by hand, so frames only propagate transforms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(default()))
+        .add_plugins((PhysicsPlugin, MotorPlugin, GroundPlugin, JumpPlugin));
+        app.update();
+        app
+    }
+
+    fn spawn_floor(app: &mut App) -> Entity {
+        app.world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
+                RigidBody::Fixed,
+                Collider::cuboid(20.0, 0.5, 20.0),
+            ))
+            .id()
+    }
+
+    /// A body tuned like the player, with its bottom `height` above the floor
+    fn spawn_body(app: &mut App, height: f32) -> Entity {
+        let stats = PlayerStats::default();
+        app.world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, height + 0.5, 0.0)),
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(0.5, 0.5, 0.5),
+                KinematicCharacterController::default(),
+                stats.character_motor(),
+                CharacterMotorState::default(),
+                stats.jump.clone(),
+                JumpState::default(),
+                GroundSensor {
+                    half_extents: Vec3::splat(0.45),
+                    max_distance: 0.15,
+                    ..default()
+                },
+            ))
+            .id()
+    }
+
+    fn tick(app: &mut App, body: Entity, pressed: bool, held: bool) {
+        let mut state = app.world.get_mut::<JumpState>(body).unwrap();
+        state.jump_pressed = pressed;
+        state.jump_held = held;
+        app.world.run_schedule(FixedUpdate);
+        app.update();
+    }
+
+    fn settle(app: &mut App, body: Entity) {
+        for _ in 0..30 {
+            tick(app, body, false, false);
+        }
+        assert!(app.world.get::<Grounded>(body).is_some());
+    }
+
+    fn velocity(app: &App, body: Entity) -> Vec3 {
+        app.world.get::<CharacterMotorState>(body).unwrap().velocity
+    }
+
+    fn height(app: &App, body: Entity) -> f32 {
+        app.world.get::<Transform>(body).unwrap().translation.y
+    }
+
+    /// Highest the body gets after the given ticks of input, then without input
+    fn peak(app: &mut App, body: Entity, inputs: &[(bool, bool)]) -> f32 {
+        let mut peak = height(app, body);
+        for &(pressed, held) in inputs {
+            tick(app, body, pressed, held);
+            peak = peak.max(height(app, body));
+        }
+        for _ in 0..60 {
+            tick(app, body, false, false);
+            peak = peak.max(height(app, body));
+        }
+        peak
+    }
+
+    fn ticks(seconds: f32) -> usize {
+        (seconds / PHYSICS_TIMESTEP).round() as usize
+    }
+
+    #[test]
+    fn coyote_time_allows_a_late_jump() {
+        let jump = Jump::default();
+        for (airborne_ticks, jumps) in [(ticks(jump.coyote_time) - 2, true), (20, false)] {
+            let mut app = app();
+            let floor = spawn_floor(&mut app);
+            let body = spawn_body(&mut app, 0.0);
+            settle(&mut app, body);
+
+            app.world.despawn(floor);
+            for _ in 0..airborne_ticks {
+                tick(&mut app, body, false, false);
+            }
+            tick(&mut app, body, true, true);
+            assert_eq!(
+                velocity(&app, body).y > 0.0,
+                jumps,
+                "{airborne_ticks} ticks"
+            );
+        }
+    }
+
+    #[test]
+    fn buffered_press_jumps_on_landing() {
+        let mut app = app();
+        spawn_floor(&mut app);
+        let body = spawn_body(&mut app, 0.3);
+
+        // a tap released long before landing still gets a full jump
+        tick(&mut app, body, true, false);
+        let mut jumped = false;
+        for _ in 0..ticks(Jump::default().buffer_time) {
+            tick(&mut app, body, false, false);
+            jumped |= app.world.get::<JumpState>(body).unwrap().is_jumping();
+        }
+        assert!(jumped);
+        let buffered_peak = peak(&mut app, body, &[]);
+
+        let mut app = self::app();
+        spawn_floor(&mut app);
+        let body = spawn_body(&mut app, 0.0);
+        settle(&mut app, body);
+        let held_peak = peak(&mut app, body, &[(true, true); 30]);
+        assert!((buffered_peak - held_peak).abs() < 0.1);
+    }
+
+    #[test]
+    fn stale_buffered_press_is_dropped() {
+        let mut app = app();
+        spawn_floor(&mut app);
+        let body = spawn_body(&mut app, 3.0);
+
+        tick(&mut app, body, true, false);
+        for _ in 0..60 {
+            tick(&mut app, body, false, false);
+            assert!(!app.world.get::<JumpState>(body).unwrap().is_jumping());
+        }
+        assert!(app.world.get::<Grounded>(body).is_some());
+    }
+
+    #[test]
+    fn releasing_jump_early_cuts_it() {
+        let mut app = app();
+        spawn_floor(&mut app);
+        let body = spawn_body(&mut app, 0.0);
+        settle(&mut app, body);
+        let held_peak = peak(&mut app, body, &[(true, true); 30]);
+
+        settle(&mut app, body);
+        let released_peak = peak(&mut app, body, &[(true, true), (false, true)]);
+        assert!(released_peak < held_peak * 0.6);
+    }
+
+    #[test]
+    fn falling_is_capped_at_max_fall_speed() {
+        let mut app = app();
+        let body = spawn_body(&mut app, 100.0);
+        for _ in 0..120 {
+            tick(&mut app, body, false, false);
+        }
+        let max_fall_speed = PlayerStats::default().max_fall_speed;
+        assert!((velocity(&app, body).y + max_fall_speed).abs() < 1e-3);
+    }
+}
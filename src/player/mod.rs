@@ -0,0 +1,284 @@
+mod dash;
+mod ground;
+mod jump;
+mod motor;
+mod stats;
+mod surface;
+
+use std::f32::consts::PI;
+
+use crate::actions::{Action, FixedActionState};
+use crate::checkpoint::RespawnPoint;
+use crate::particles::{ParticleBurst, ParticleEmitter};
+use crate::physics::{FixedStepSet, RenderInterpolation};
+use crate::third_person_camera::ThirdPersonCameraTarget;
+use crate::trigger::TriggerTags;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use dash::DashPlugin;
+use ground::GroundPlugin;
+use jump::JumpPlugin;
+use motor::MotorPlugin;
+use stats::StatsPlugin;
+use surface::SurfacePlugin;
+
+pub use dash::{apply_dash, Dash, DashEnded, DashStarted, DashState};
+pub use ground::{GroundSensor, GroundSensorSet, Grounded, Landed, LeftGround};
+pub use jump::{apply_jump, Jump, JumpState};
+pub use motor::{CharacterMotor, CharacterMotorSet, CharacterMotorState};
+pub use stats::{PlayerStats, PlayerStatsHandle};
+pub use surface::SurfaceType;
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MotorPlugin,
+            GroundPlugin,
+            JumpPlugin,
+            DashPlugin,
+            StatsPlugin,
+            SurfacePlugin,
+        ))
+        .add_systems(Startup, spawn_player)
+        .add_systems(
+            FixedUpdate,
+            player_movement
+                .in_set(PlayerControlSet)
+                .in_set(FixedStepSet::Simulate)
+                .before(CharacterMotorSet),
+        )
+        .add_systems(Update, spawn_particles_on_player_dash);
+    }
+}
+
+/// Systems turning the player's actions into movement
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerControlSet;
+
+#[derive(Component)]
+pub struct Player;
+
+/// [`TriggerTags`] tag of the player, for triggers that only react to it
+pub const PLAYER_TAG: &str = "player";
+
+/// Walking speed in world units per second
+#[derive(Component)]
+pub struct MoveSpeed(pub f32);
+
+/// How quickly the player turns to face where it's walking. Higher is snappier
+#[derive(Component)]
+pub struct RotateSpeed(f32);
+
+pub fn spawn_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    stats: Res<PlayerStats>,
+) {
+    let transform = Transform::from_xyz(0.0, 0.5, 0.0);
+    commands
+        .spawn((
+            Name::new("Player"),
+            PbrBundle {
+                transform,
+                mesh: meshes.add(Mesh::from(shape::Cube::new(1.0))),
+                material: materials.add(Color::BLUE.into()),
+                ..default()
+            },
+            ThirdPersonCameraTarget,
+            RenderInterpolation::default(),
+            RespawnPoint::from_transform(transform),
+        ))
+        .insert((
+            RigidBody::KinematicPositionBased,
+            Collider::cuboid(0.5, 0.5, 0.5),
+            character_controller(),
+            stats.character_motor(),
+            CharacterMotorState::default(),
+        ))
+        .insert((
+            Player,
+            MoveSpeed(stats.move_speed),
+            stats.dash.clone(),
+            DashState::default(),
+            stats.jump.clone(),
+            JumpState::default(),
+            GroundSensor {
+                half_extents: Vec3::new(0.45, 0.45, 0.45),
+                max_distance: 0.15,
+                ..default()
+            },
+            RotateSpeed(stats.rotate_speed),
+            TriggerTags::new([PLAYER_TAG]),
+            ParticleEmitter {
+                burst: 32,
+                cone_angle: PI / 6.0,
+                min_speed: 8.0,
+                max_speed: 16.0,
+                lifetime: 0.5,
+                gravity: Vec3::NEG_Y * 20.0,
+                drag: 4.0,
+                start_color: Color::ANTIQUE_WHITE,
+                end_color: Color::ANTIQUE_WHITE.with_a(0.0),
+                start_size: 0.1,
+                end_size: 0.02,
+                max_particles: 64,
+                ..default()
+            },
+        ));
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn player_movement(
+    mut player_query: Query<
+        (
+            Entity,
+            &mut CharacterMotorState,
+            &mut Transform,
+            &mut DashState,
+        ),
+        With<Player>,
+    >,
+    move_speed_query: Query<&MoveSpeed>,
+    rotate_speed_query: Query<&RotateSpeed>,
+    camera_query: Query<&Transform, (With<Camera3d>, Without<Player>)>,
+    actions: Res<FixedActionState>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (player_entity, mut motor, mut transform, mut dash_state) in player_query.iter_mut() {
+        let camera = camera_query.get_single().expect("Could not find camera");
+
+        let move_input = actions.axis(Action::Move);
+        let mut move_direction = move_input.y * camera.forward() + move_input.x * camera.right();
+        move_direction.y = 0.0;
+        move_direction = move_direction.normalize_or_zero();
+
+        let move_speed = move_speed_query
+            .get(player_entity)
+            .unwrap_or(&MoveSpeed(1.0))
+            .0;
+        motor.move_velocity = move_speed * move_direction;
+
+        if actions.just_pressed(Action::Dash) && move_direction.length_squared() > 0.0 {
+            dash_state.requested_direction = Some(move_direction);
+        }
+
+        if move_direction.length_squared() > 0.0 {
+            let rotate_speed = rotate_speed_query
+                .get(player_entity)
+                .unwrap_or(&RotateSpeed(1.0))
+                .0;
+            // exponential smoothing, so turning takes the same time at any tick rate
+            let forward = transform.forward();
+            transform.look_to(
+                forward.lerp(move_direction, 1.0 - (-rotate_speed * dt).exp()),
+                Vec3::Y,
+            );
+        }
+    }
+}
+
+/// Debris like dash particles is pushed around instead of blocking the player, and sensors like
+/// checkpoints and triggers are walked through
+fn character_controller() -> KinematicCharacterController {
+    KinematicCharacterController {
+        filter_flags: QueryFilterFlags::EXCLUDE_DYNAMIC | QueryFilterFlags::EXCLUDE_SENSORS,
+        ..default()
+    }
+}
+
+fn spawn_particles_on_player_dash(
+    mut dash_event_reader: EventReader<DashStarted>,
+    mut burst_writer: EventWriter<ParticleBurst>,
+) {
+    for dash_event in dash_event_reader.iter() {
+        burst_writer.send(ParticleBurst {
+            emitter: dash_event.entity,
+            origin: dash_event.start_position,
+            direction: -dash_event.direction,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PhysicsPlugin;
+    use crate::trigger::{TriggerEntered, TriggerPlugin, TriggerVolume};
+    use bevy::{scene::SceneSpawner, time::TimeUpdateStrategy};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        .init_resource::<SceneSpawner>()
+        // ticks are run by hand, so frames only propagate transforms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(default()))
+        .add_plugins((PhysicsPlugin, MotorPlugin, GroundPlugin, TriggerPlugin));
+        app.update();
+        app
+    }
+
+    #[test]
+    fn walks_through_trigger_volumes() {
+        let mut app = app();
+        app.world.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(0.0, -0.5, 0.0)),
+            RigidBody::Fixed,
+            Collider::cuboid(20.0, 0.5, 20.0),
+        ));
+        // standing on the floor across the whole path, like a checkpoint
+        let trigger = app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(4.0, 1.0, 0.0)),
+                Collider::cuboid(1.0, 1.0, 5.0),
+                Sensor,
+                TriggerVolume::tagged(PLAYER_TAG),
+            ))
+            .id();
+        let stats = PlayerStats::default();
+        let player = app
+            .world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_xyz(0.0, 0.5, 0.0)),
+                RigidBody::KinematicPositionBased,
+                Collider::cuboid(0.5, 0.5, 0.5),
+                character_controller(),
+                stats.character_motor(),
+                CharacterMotorState::default(),
+                TriggerTags::new([PLAYER_TAG]),
+            ))
+            .id();
+
+        let mut entered = vec![];
+        for _ in 0..120 {
+            app.world
+                .get_mut::<CharacterMotorState>(player)
+                .unwrap()
+                .move_velocity = Vec3::X * stats.move_speed;
+            app.world.run_schedule(FixedUpdate);
+            app.update();
+            entered.extend(
+                app.world
+                    .resource_mut::<Events<TriggerEntered>>()
+                    .drain()
+                    .map(|entered| (entered.trigger, entered.entity)),
+            );
+        }
+
+        let position = app.world.get::<Transform>(player).unwrap().translation;
+        assert!(position.x > 8.0, "stopped at {position}");
+        assert_eq!(entered, vec![(trigger, player)]);
+    }
+}
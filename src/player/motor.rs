@@ -0,0 +1,159 @@
+use crate::physics::FixedStepSet;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+pub struct MotorPlugin;
+
+impl Plugin for MotorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            apply_character_motors
+                .in_set(CharacterMotorSet)
+                .in_set(FixedStepSet::Simulate),
+        );
+    }
+}
+
+/// Systems that change a [`CharacterMotorState`] should run before this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharacterMotorSet;
+
+/// Movement tuning for a kinematic body driven by a [`KinematicCharacterController`].
+///
+/// The motor integrates gravity, damping and impulses into a velocity and hands the resulting
+/// displacement to the controller, which resolves collisions, slopes and steps.
+#[derive(Component, Clone)]
+pub struct CharacterMotor {
+    /// Downward acceleration in units per second squared
+    pub gravity: f32,
+    /// Downward speed is clamped to this value
+    pub max_fall_speed: f32,
+    /// How quickly velocity from impulses dies down while grounded
+    pub ground_damping: f32,
+    /// How quickly horizontal velocity from impulses dies down while airborne
+    pub air_damping: f32,
+    /// Steepest slope, in radians, that can be walked up
+    pub max_slope_climb_angle: f32,
+    /// Slopes steeper than this, in radians, are slid down
+    pub min_slope_slide_angle: f32,
+    /// Small obstacles that are stepped over. `None` to disable
+    pub autostep: Option<CharacterAutostep>,
+    /// Distance under which the body is pulled down onto the ground it walks on. `None` to disable
+    pub snap_to_ground: Option<CharacterLength>,
+}
+
+impl Default for CharacterMotor {
+    fn default() -> Self {
+        Self {
+            gravity: 9.81,
+            max_fall_speed: f32::INFINITY,
+            ground_damping: 10.0,
+            air_damping: 0.0,
+            max_slope_climb_angle: 45.0_f32.to_radians(),
+            min_slope_slide_angle: 30.0_f32.to_radians(),
+            autostep: Some(CharacterAutostep::default()),
+            snap_to_ground: Some(CharacterLength::Relative(0.2)),
+        }
+    }
+}
+
+/// Per-entity velocity of a [`CharacterMotor`].
+///
+/// Gameplay systems write to this instead of moving the body directly.
+#[derive(Component)]
+pub struct CharacterMotorState {
+    /// Velocity affected by gravity, damping and collisions
+    pub velocity: Vec3,
+    /// Walking velocity for this tick. It is added on top of `velocity` and cleared once read
+    pub move_velocity: Vec3,
+    /// While set, gravity and damping leave `velocity` untouched
+    pub hold_velocity: bool,
+    /// Grip on the ground for this tick. Below `1.0`, walking speeds up and slows down
+    /// gradually and impulses slide for longer. Reset to `1.0` once read
+    pub traction: f32,
+    /// Velocity of the ground for this tick, e.g. a conveyor. Cleared once read
+    pub surface_velocity: Vec3,
+}
+
+impl Default for CharacterMotorState {
+    fn default() -> Self {
+        Self {
+            velocity: Vec3::ZERO,
+            move_velocity: Vec3::ZERO,
+            hold_velocity: false,
+            traction: 1.0,
+            surface_velocity: Vec3::ZERO,
+        }
+    }
+}
+
+impl CharacterMotorState {
+    /// Adds an instant change in velocity, e.g. knockback
+    pub fn apply_impulse(&mut self, impulse: Vec3) {
+        self.velocity += impulse;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn apply_character_motors(
+    mut motor_query: Query<(
+        &CharacterMotor,
+        &mut CharacterMotorState,
+        &mut KinematicCharacterController,
+        Option<&KinematicCharacterControllerOutput>,
+    )>,
+    fixed_time: Res<FixedTime>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (motor, mut state, mut controller, output) in motor_query.iter_mut() {
+        let grounded = output.is_some_and(|output| output.grounded);
+
+        // stop moving into whatever the last move ran into, so landing on the ground or
+        // bumping into a ceiling cancels the velocity that caused it
+        for collision in output.iter().flat_map(|output| output.collisions.iter()) {
+            if collision.toi.status == TOIStatus::Penetrating {
+                continue;
+            }
+            let normal = collision.toi.normal1;
+            let into_surface = state.velocity.dot(normal);
+            if into_surface < 0.0 {
+                state.velocity -= normal * into_surface;
+            }
+        }
+
+        let mut move_velocity = std::mem::take(&mut state.move_velocity);
+        let traction = std::mem::replace(&mut state.traction, 1.0);
+        let surface_velocity = std::mem::take(&mut state.surface_velocity);
+
+        if !state.hold_velocity {
+            state.velocity.y = (state.velocity.y - motor.gravity * dt).max(-motor.max_fall_speed);
+
+            // on slippery ground walking becomes momentum, eased in and out like impulses
+            let mut target = Vec3::ZERO;
+            let damping = if grounded && traction < 1.0 {
+                target = std::mem::take(&mut move_velocity);
+                motor.ground_damping * traction
+            } else if grounded {
+                motor.ground_damping
+            } else {
+                motor.air_damping
+            };
+            let decay = (-damping * dt).exp();
+            state.velocity.x = target.x + (state.velocity.x - target.x) * decay;
+            state.velocity.z = target.z + (state.velocity.z - target.z) * decay;
+        }
+
+        controller.max_slope_climb_angle = motor.max_slope_climb_angle;
+        controller.min_slope_slide_angle = motor.min_slope_slide_angle;
+        controller.autostep = motor.autostep;
+        // snapping while rising would pull the body back down on the first tick of a jump
+        controller.snap_to_ground = if state.velocity.y > 0.0 {
+            None
+        } else {
+            motor.snap_to_ground
+        };
+        controller.translation = Some((state.velocity + move_velocity + surface_velocity) * dt);
+    }
+}
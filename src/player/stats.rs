@@ -0,0 +1,154 @@
+use crate::player::{CharacterMotor, Dash, Jump, MoveSpeed, Player, RotateSpeed};
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::{TypePath, TypeUuid},
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<PlayerStats>()
+            .init_asset_loader::<PlayerStatsLoader>()
+            .init_resource::<PlayerStats>()
+            .add_systems(Startup, load_player_stats)
+            .add_systems(
+                Update,
+                (
+                    apply_loaded_player_stats,
+                    apply_player_stats.run_if(resource_changed::<PlayerStats>()),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Movement tuning of the player, loaded from `player.stats.ron`.
+///
+/// Saving the file re-applies it to every live [`Player`].
+#[derive(Resource, Clone, Debug, Serialize, Deserialize, TypeUuid, TypePath)]
+#[uuid = "8f1e5b0a-6c2d-4f37-a4f4-2d9b1c7e5a63"]
+#[serde(default)]
+pub struct PlayerStats {
+    /// Walking speed in world units per second
+    pub move_speed: f32,
+    /// How quickly the player turns to face where it's walking
+    pub rotate_speed: f32,
+    /// Downward acceleration in units per second squared
+    pub gravity: f32,
+    pub max_fall_speed: f32,
+    pub ground_damping: f32,
+    pub air_damping: f32,
+    pub jump: Jump,
+    pub dash: Dash,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            move_speed: 6.0,
+            rotate_speed: 16.0,
+            gravity: 8.0 * 9.81,
+            max_fall_speed: 40.0,
+            ground_damping: 10.0,
+            air_damping: 2.0,
+            jump: Jump::default(),
+            dash: Dash::default(),
+        }
+    }
+}
+
+impl PlayerStats {
+    /// Motor with these stats on top of the default slope and step handling
+    pub fn character_motor(&self) -> CharacterMotor {
+        let mut motor = CharacterMotor::default();
+        self.apply_to_motor(&mut motor);
+        motor
+    }
+
+    fn apply_to_motor(&self, motor: &mut CharacterMotor) {
+        motor.gravity = self.gravity;
+        motor.max_fall_speed = self.max_fall_speed;
+        motor.ground_damping = self.ground_damping;
+        motor.air_damping = self.air_damping;
+    }
+}
+
+#[derive(Default)]
+pub struct PlayerStatsLoader;
+
+impl AssetLoader for PlayerStatsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let stats = ron::de::from_bytes::<PlayerStats>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(stats));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["stats.ron"]
+    }
+}
+
+#[derive(Resource)]
+pub struct PlayerStatsHandle(pub Handle<PlayerStats>);
+
+fn load_player_stats(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(PlayerStatsHandle(asset_server.load("player.stats.ron")));
+}
+
+fn apply_loaded_player_stats(
+    mut events: EventReader<AssetEvent<PlayerStats>>,
+    handle: Option<Res<PlayerStatsHandle>>,
+    assets: Res<Assets<PlayerStats>>,
+    mut stats: ResMut<PlayerStats>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle: h } | AssetEvent::Modified { handle: h }
+                if *h == handle.0 =>
+            {
+                if let Some(loaded) = assets.get(h) {
+                    *stats = loaded.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_player_stats(
+    mut player_query: Query<
+        (
+            &mut MoveSpeed,
+            &mut RotateSpeed,
+            &mut CharacterMotor,
+            &mut Jump,
+            &mut Dash,
+        ),
+        With<Player>,
+    >,
+    stats: Res<PlayerStats>,
+) {
+    for (mut move_speed, mut rotate_speed, mut motor, mut jump, mut dash) in player_query.iter_mut()
+    {
+        move_speed.0 = stats.move_speed;
+        rotate_speed.0 = stats.rotate_speed;
+        stats.apply_to_motor(&mut motor);
+        *jump = stats.jump.clone();
+        *dash = stats.dash.clone();
+    }
+}
@@ -0,0 +1,166 @@
+use crate::physics::FixedStepSet;
+use crate::player::{
+    apply_dash, apply_jump, CharacterMotorSet, CharacterMotorState, DashState, GroundSensorSet,
+    Grounded, Landed,
+};
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub struct SurfacePlugin;
+
+impl Plugin for SurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            apply_surfaces
+                .in_set(FixedStepSet::Simulate)
+                .after(GroundSensorSet)
+                .before(apply_jump)
+                .before(apply_dash)
+                .before(CharacterMotorSet),
+        )
+        .add_systems(Update, apply_surface_physics);
+    }
+}
+
+/// How a character standing on this collider, or on one of its children, moves
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum SurfaceType {
+    /// Slippery ground that keeps momentum. Lower traction makes speeding up and slowing down
+    /// take longer
+    Ice {
+        #[serde(default = "default_ice_traction")]
+        traction: f32,
+    },
+    /// Trampoline that sends characters landing on it back up with a fraction of their fall
+    /// speed
+    Bouncy {
+        #[serde(default = "default_restitution")]
+        restitution: f32,
+    },
+    /// Moves whatever stands on it, with a velocity in the surface's local space
+    Conveyor { velocity: Vec3 },
+    /// Ground that can't be dashed from
+    Sticky,
+}
+
+fn default_ice_traction() -> f32 {
+    0.1
+}
+
+fn default_restitution() -> f32 {
+    0.9
+}
+
+impl SurfaceType {
+    /// Material that tells the surface apart at a glance
+    pub fn material(&self) -> StandardMaterial {
+        match self {
+            SurfaceType::Ice { .. } => StandardMaterial {
+                base_color: Color::rgb(0.75, 0.9, 1.0),
+                perceptual_roughness: 0.05,
+                reflectance: 0.9,
+                ..default()
+            },
+            SurfaceType::Bouncy { .. } => StandardMaterial {
+                base_color: Color::rgb(1.0, 0.3, 0.65),
+                perceptual_roughness: 0.4,
+                ..default()
+            },
+            SurfaceType::Conveyor { .. } => StandardMaterial {
+                base_color: Color::rgb(0.25, 0.25, 0.28),
+                emissive: Color::rgb(0.4, 0.2, 0.0),
+                metallic: 0.6,
+                perceptual_roughness: 0.5,
+                ..default()
+            },
+            SurfaceType::Sticky => StandardMaterial {
+                base_color: Color::rgb(0.45, 0.25, 0.55),
+                perceptual_roughness: 1.0,
+                ..default()
+            },
+        }
+    }
+}
+
+fn ground_surface(
+    ground: Entity,
+    surface_query: &Query<(&SurfaceType, &GlobalTransform)>,
+    parent_query: &Query<&Parent>,
+) -> Option<(SurfaceType, Quat)> {
+    // the collider may be a child of the entity that has the surface
+    std::iter::once(ground)
+        .chain(parent_query.iter_ancestors(ground))
+        .find_map(|entity| surface_query.get(entity).ok())
+        .map(|(surface, transform)| (*surface, transform.to_scale_rotation_translation().1))
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_surfaces(
+    mut character_query: Query<(
+        &mut CharacterMotorState,
+        Option<&Grounded>,
+        Option<&mut DashState>,
+    )>,
+    surface_query: Query<(&SurfaceType, &GlobalTransform)>,
+    parent_query: Query<&Parent>,
+    mut landed_reader: EventReader<Landed>,
+) {
+    for landed in landed_reader.iter() {
+        let Some((SurfaceType::Bouncy { restitution }, _)) =
+            ground_surface(landed.ground, &surface_query, &parent_query)
+        else {
+            continue;
+        };
+        let Ok((mut motor, _, _)) = character_query.get_mut(landed.entity) else {
+            continue;
+        };
+        motor.velocity.y = motor
+            .velocity
+            .y
+            .max(-landed.impact_velocity.y * restitution);
+    }
+
+    for (mut motor, grounded, dash) in character_query.iter_mut() {
+        let surface = grounded
+            .and_then(|grounded| ground_surface(grounded.entity, &surface_query, &parent_query));
+
+        match surface {
+            Some((SurfaceType::Ice { traction }, _)) => motor.traction = traction,
+            Some((SurfaceType::Conveyor { velocity }, rotation)) => {
+                motor.surface_velocity = rotation * velocity;
+            }
+            Some((SurfaceType::Sticky, _)) => {
+                if let Some(mut dash) = dash {
+                    dash.blocked = true;
+                }
+            }
+            Some((SurfaceType::Bouncy { .. }, _)) | None => {}
+        }
+    }
+}
+
+/// Gives dynamic bodies a matching feel on ice and trampolines
+fn apply_surface_physics(
+    mut commands: Commands,
+    surface_query: Query<(Entity, &SurfaceType), Changed<SurfaceType>>,
+) {
+    for (entity, surface) in surface_query.iter() {
+        match *surface {
+            SurfaceType::Ice { traction } => {
+                commands.entity(entity).insert(Friction {
+                    coefficient: traction * 0.5,
+                    combine_rule: CoefficientCombineRule::Min,
+                });
+            }
+            SurfaceType::Bouncy { restitution } => {
+                commands.entity(entity).insert(Restitution {
+                    coefficient: restitution,
+                    combine_rule: CoefficientCombineRule::Max,
+                });
+            }
+            SurfaceType::Conveyor { .. } | SurfaceType::Sticky => {}
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{GamepadResource, ThirdPersonCamera};
+use crate::{CameraOrbitInput, CameraOrbitInputSet, GamepadResource, ThirdPersonCamera};
 use bevy::{
     input::gamepad::{GamepadConnection::*, *},
     prelude::*,
@@ -14,7 +14,8 @@ impl Plugin for GamePadPlugin {
             Update,
             (
                 connections,
-                (orbit_gamepad, zoom_gamepad).run_if(resource_exists::<GamepadResource>()),
+                orbit_gamepad.in_set(CameraOrbitInputSet),
+                zoom_gamepad,
             ),
         );
     }
@@ -25,17 +26,24 @@ fn connections(
     gamepad_res: Option<Res<GamepadResource>>,
     mut gamepad_evr: EventReader<GamepadConnectionEvent>,
 ) {
+    // tracked locally since the commands below only apply after this system
+    let mut current = gamepad_res.map(|gamepad| gamepad.0);
     for ev in gamepad_evr.iter() {
         match &ev.connection {
             Connected(_info) => {
                 // if no gamepad is setup yet, use this one
-                if gamepad_res.is_none() {
-                    cmds.insert_resource(GamepadResource(Gamepad::new(0)));
+                if current.is_none() {
+                    cmds.insert_resource(GamepadResource(ev.gamepad));
+                    current = Some(ev.gamepad);
                 }
                 // println!("Gamepad connected");
             }
             Disconnected => {
-                cmds.remove_resource::<GamepadResource>();
+                // other pads coming and going don't affect the one in use
+                if current == Some(ev.gamepad) {
+                    cmds.remove_resource::<GamepadResource>();
+                    current = None;
+                }
                 // println!("Gamepad disconnected");
             }
         }
@@ -45,19 +53,17 @@ fn connections(
 pub fn zoom_gamepad(
     btns: Res<Input<GamepadButton>>,
     gamepad_res: Option<Res<GamepadResource>>,
-    mut cam_q: Query<&mut ThirdPersonCamera, With<ThirdPersonCamera>>,
+    mut cam_q: Query<&mut ThirdPersonCamera>,
 ) {
-    let gamepad = if let Some(gp) = gamepad_res {
-        gp.0
-    } else {
-        return;
-    };
+    for mut cam in cam_q.iter_mut() {
+        let Some(gamepad) = cam.input.gamepad(gamepad_res.as_deref()) else {
+            continue;
+        };
 
-    if let Ok(mut cam) = cam_q.get_single_mut() {
         let gp = &cam.gamepad_settings;
 
-        let zoom_out = GamepadButton::new(gamepad, gp.zoom_out_button.button_type);
-        let zoom_in = GamepadButton::new(gamepad, gp.zoom_in_button.button_type);
+        let zoom_out = GamepadButton::new(gamepad, gp.zoom_out_button);
+        let zoom_in = GamepadButton::new(gamepad, gp.zoom_in_button);
 
         let mut new_radius = cam.zoom.radius;
 
@@ -75,61 +81,44 @@ pub fn zoom_gamepad(
 
 pub fn orbit_gamepad(
     window_q: Query<&Window, With<PrimaryWindow>>,
-    mut cam_q: Query<(&ThirdPersonCamera, &mut Transform), With<ThirdPersonCamera>>,
+    cam_q: Query<(Entity, &ThirdPersonCamera)>,
     btns: Res<Input<GamepadButton>>,
     axis: Res<Axis<GamepadAxis>>,
     gamepad_res: Option<Res<GamepadResource>>,
+    mut orbit_writer: EventWriter<CameraOrbitInput>,
 ) {
-    // return gamepad if one is connected
-    let gamepad = if let Some(gp) = gamepad_res {
-        gp.0
-    } else {
-        return;
-    };
-
-    let Ok((cam, mut cam_transform)) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    if cam.mouse_orbit_button_enabled && !btns.pressed(cam.gamepad_settings.mouse_orbit_button) {
-        return;
-    }
-
-    let x_axis = GamepadAxis::new(gamepad, GamepadAxisType::RightStickX);
-    let y_axis = GamepadAxis::new(gamepad, GamepadAxisType::RightStickY);
+    for (entity, cam) in cam_q.iter() {
+        // skip cameras without a connected gamepad
+        let Some(gamepad) = cam.input.gamepad(gamepad_res.as_deref()) else {
+            continue;
+        };
 
-    let deadzone = 0.5;
-    let mut rotation = Vec2::ZERO;
-    if let (Some(x), Some(y)) = (axis.get(x_axis), axis.get(y_axis)) {
-        if x.abs() > deadzone || y.abs() > deadzone {
-            rotation = Vec2::new(x, y);
+        let orbit_button = GamepadButton::new(gamepad, cam.gamepad_settings.mouse_orbit_button);
+        if cam.mouse_orbit_button_enabled && !btns.pressed(orbit_button) {
+            continue;
         }
-    }
 
-    if rotation.length_squared() > 0.0 {
-        let window = window_q.get_single().unwrap();
-        let delta_x = {
-            let delta = rotation.x / window.width()
-                * std::f32::consts::PI
-                * 2.0
-                * cam.gamepad_settings.x_sensitivity;
-            delta
-        };
-        let delta_y = -rotation.y / window.height() * PI * cam.gamepad_settings.y_sensitivity;
-        let yaw = Quat::from_rotation_y(-delta_x);
-        let pitch = Quat::from_rotation_x(-delta_y);
-        cam_transform.rotation = yaw * cam_transform.rotation; // rotate around global y axis
+        let x_axis = GamepadAxis::new(gamepad, GamepadAxisType::RightStickX);
+        let y_axis = GamepadAxis::new(gamepad, GamepadAxisType::RightStickY);
 
-        let new_rotation = cam_transform.rotation * pitch;
+        let deadzone = 0.5;
+        let mut rotation = Vec2::ZERO;
+        if let (Some(x), Some(y)) = (axis.get(x_axis), axis.get(y_axis)) {
+            if x.abs() > deadzone || y.abs() > deadzone {
+                rotation = Vec2::new(x, y);
+            }
+        }
 
-        // check if new rotation will cause camera to go beyond the 180 degree vertical bounds
-        let up_vector = new_rotation * Vec3::Y;
-        if up_vector.y > 0.0 {
-            cam_transform.rotation = new_rotation;
+        if rotation.length_squared() > 0.0 {
+            let window = window_q.get_single().unwrap();
+            let delta = Vec2::new(
+                rotation.x / window.width() * 2.0,
+                -rotation.y / window.height(),
+            ) * PI;
+            orbit_writer.send(CameraOrbitInput {
+                delta: cam.gamepad_settings.orbit.apply(delta),
+                camera: Some(entity),
+            });
         }
     }
-
-    let rot_matrix = Mat3::from_quat(cam_transform.rotation);
-    cam_transform.translation =
-        cam.focus + rot_matrix.mul_vec3(Vec3::new(0.0, 0.0, cam.zoom.radius));
 }
@@ -1,14 +1,21 @@
 mod gamepad;
 mod mouse;
+mod orbit;
 mod touch;
 
 use bevy::{
     prelude::*,
     window::{CursorGrabMode, PrimaryWindow},
 };
-use gamepad::{orbit_gamepad, GamePadPlugin};
-use mouse::{orbit_mouse, MousePlugin};
-use touch::{orbit_touch, TouchPlugin};
+use bevy_rapier3d::prelude::*;
+use gamepad::GamePadPlugin;
+use mouse::MousePlugin;
+use orbit::OrbitPlugin;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use touch::TouchPlugin;
+
+pub use orbit::{CameraOrbitInput, CameraOrbitInputSet, CameraOrbitSet, OrbitInputSettings};
 
 /// # Examples
 ///
@@ -23,15 +30,14 @@ pub struct ThirdPersonCameraPlugin;
 
 impl Plugin for ThirdPersonCameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((MousePlugin, GamePadPlugin, TouchPlugin))
+        app.add_plugins((OrbitPlugin, MousePlugin, GamePadPlugin, TouchPlugin))
             .add_systems(
                 Update,
                 (
                     aim.run_if(aim_condition),
                     sync_player_camera
-                        .after(orbit_mouse)
-                        .after(orbit_gamepad)
-                        .after(orbit_touch),
+                        .in_set(ThirdPersonCameraSyncSet)
+                        .after(CameraOrbitSet),
                     toggle_x_offset.run_if(toggle_x_offset_condition),
                     toggle_cursor.run_if(toggle_cursor_condition),
                 ),
@@ -39,6 +45,11 @@ impl Plugin for ThirdPersonCameraPlugin {
     }
 }
 
+/// The system that moves the camera along with its target. Anything that moves the target in
+/// [`Update`] should run before it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThirdPersonCameraSyncSet;
+
 /// # Examples
 ///
 /// ```
@@ -57,21 +68,31 @@ pub struct ThirdPersonCamera {
     pub aim_button: MouseButton,
     pub aim_speed: f32,
     pub aim_zoom: f32,
+    pub collision: CameraCollision,
     pub cursor_lock_toggle_enabled: bool,
     pub cursor_lock_active: bool,
     pub cursor_lock_key: KeyCode,
     pub focus: Vec3,
     pub gamepad_settings: CustomGamepadSettings,
+    /// Devices that control this camera
+    pub input: CameraInputSources,
     pub touch_settings: CustomTouchSettings,
     pub orbit_enabled: bool,
-    pub mouse_sensitivity: f32,
+    pub mouse_orbit: OrbitInputSettings,
     pub mouse_orbit_button_enabled: bool,
     pub mouse_orbit_button: MouseButton,
     pub offset_enabled: bool,
     pub offset: Offset,
+    /// Where the camera looks from, which the orbit systems turn and the transform follows
+    pub orbit: Orbit,
+    pub orbit_limits: OrbitLimits,
     pub offset_toggle_enabled: bool,
     pub offset_toggle_key: KeyCode,
     pub offset_toggle_speed: f32,
+    pub smoothing: CameraSmoothing,
+    /// Entity the camera follows, or `None` for the only [`ThirdPersonCameraTarget`]. It can be
+    /// changed at any time, e.g. to follow a vehicle the player got into
+    pub target: Option<Entity>,
     pub zoom_enabled: bool,
     pub zoom: Zoom,
     pub zoom_sensitivity: f32,
@@ -84,21 +105,27 @@ impl Default for ThirdPersonCamera {
             aim_button: MouseButton::Right,
             aim_speed: 3.0,
             aim_zoom: 0.7,
+            collision: CameraCollision::default(),
             cursor_lock_key: KeyCode::Space,
             cursor_lock_toggle_enabled: true,
             focus: Vec3::ZERO,
             gamepad_settings: CustomGamepadSettings::default(),
+            input: CameraInputSources::default(),
             touch_settings: CustomTouchSettings::default(),
             cursor_lock_active: true,
             orbit_enabled: true,
-            mouse_sensitivity: 1.0,
+            mouse_orbit: OrbitInputSettings::default(),
             mouse_orbit_button_enabled: false,
             mouse_orbit_button: MouseButton::Middle,
             offset_enabled: false,
             offset: Offset::new(0.5, 0.4),
+            orbit: Orbit::default(),
+            orbit_limits: OrbitLimits::default(),
             offset_toggle_enabled: false,
             offset_toggle_speed: 5.0,
             offset_toggle_key: KeyCode::E,
+            smoothing: CameraSmoothing::default(),
+            target: None,
             zoom_enabled: true,
             zoom: Zoom::new(1.5, 3.0),
             zoom_sensitivity: 1.0,
@@ -106,12 +133,149 @@ impl Default for ThirdPersonCamera {
     }
 }
 
+impl ThirdPersonCamera {
+    /// Turns the orbit by `yaw` and `pitch` radians, within [`ThirdPersonCamera::orbit_limits`]
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.orbit = self.orbit_limits.clamp(Orbit {
+            yaw: self.orbit.yaw + yaw,
+            pitch: self.orbit.pitch + pitch,
+        });
+    }
+
+    /// Skips smoothing until the next sync, e.g. after the target was teleported
+    pub fn snap(&mut self) {
+        self.smoothing.state = None;
+        self.zoom.current_radius = None;
+    }
+
+    /// The entity the camera follows: [`ThirdPersonCamera::target`], or else the
+    /// [`ThirdPersonCameraTarget`] when there is exactly one
+    pub fn resolve_target(
+        &self,
+        target_q: &Query<Entity, With<ThirdPersonCameraTarget>>,
+    ) -> Option<Entity> {
+        self.target.or_else(|| target_q.get_single().ok())
+    }
+}
+
+/// Which devices control a [`ThirdPersonCamera`]. Giving each camera its own gamepad or part of
+/// the screen lets several of them be played at once, e.g. in split-screen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraInputSources {
+    /// Whether the mouse and keyboard orbit, zoom, aim and toggle this camera
+    pub mouse: bool,
+    pub gamepad: CameraGamepad,
+    /// Part of the window, as fractions of its size, that touches have to start in to orbit this
+    /// camera. `None` ignores touches
+    pub touch_region: Option<Rect>,
+}
+
+impl Default for CameraInputSources {
+    fn default() -> Self {
+        Self {
+            mouse: true,
+            gamepad: CameraGamepad::Shared,
+            touch_region: Some(Rect::new(0.0, 0.0, 1.0, 1.0)),
+        }
+    }
+}
+
+impl CameraInputSources {
+    /// The gamepad controlling the camera, if any is connected
+    pub fn gamepad(&self, shared: Option<&GamepadResource>) -> Option<Gamepad> {
+        match self.gamepad {
+            CameraGamepad::Shared => shared.map(|gamepad| gamepad.0),
+            CameraGamepad::Gamepad(gamepad) => Some(gamepad),
+            CameraGamepad::None => None,
+        }
+    }
+
+    /// Whether a touch that started at `position` controls the camera, for a window of `size`
+    pub fn owns_touch(&self, position: Vec2, size: Vec2) -> bool {
+        self.touch_region.is_some_and(|region| {
+            Rect::from_corners(region.min * size, region.max * size).contains(position)
+        })
+    }
+}
+
+/// The gamepad controlling a [`ThirdPersonCamera`]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CameraGamepad {
+    /// Whichever gamepad is in [`GamepadResource`]
+    #[default]
+    Shared,
+    Gamepad(Gamepad),
+    None,
+}
+
+/// Direction the camera looks at its target from
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Orbit {
+    /// Radians around the Y axis. `0` looks along -Z, and it grows turning left
+    pub yaw: f32,
+    /// Radians above the horizon, negative looking down at the target
+    pub pitch: f32,
+}
+
+impl Orbit {
+    pub fn new(yaw: f32, pitch: f32) -> Self {
+        Self { yaw, pitch }
+    }
+
+    /// The orbit of a camera with `rotation`, ignoring roll
+    pub fn from_rotation(rotation: Quat) -> Self {
+        let (yaw, pitch, _) = rotation.to_euler(EulerRot::YXZ);
+        Self { yaw, pitch }
+    }
+
+    pub fn rotation(&self) -> Quat {
+        Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0)
+    }
+}
+
+/// Bounds of an [`Orbit`], in radians
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrbitLimits {
+    pub min_pitch: f32,
+    pub max_pitch: f32,
+    /// Lowest and highest yaw, or `None` to turn all the way around
+    pub yaw: Option<(f32, f32)>,
+}
+
+impl Default for OrbitLimits {
+    fn default() -> Self {
+        // just short of straight up or down, where yaw stops meaning anything
+        Self {
+            min_pitch: -FRAC_PI_2 + 0.05,
+            max_pitch: FRAC_PI_2 - 0.05,
+            yaw: None,
+        }
+    }
+}
+
+impl OrbitLimits {
+    pub fn clamp(&self, orbit: Orbit) -> Orbit {
+        let yaw = match self.yaw {
+            Some((min, max)) => orbit.yaw.clamp(min, max),
+            // kept within a turn, so it doesn't grow without bound
+            None => (orbit.yaw + PI).rem_euclid(TAU) - PI,
+        };
+        Orbit {
+            yaw,
+            pitch: orbit.pitch.clamp(self.min_pitch, self.max_pitch),
+        }
+    }
+}
+
 /// Sets the zoom bounds (min & max)
 pub struct Zoom {
     pub min: f32,
     pub max: f32,
+    /// Radius the camera moves towards, see [`CameraSmoothing::zoom`]
     pub radius: f32,
     radius_copy: Option<f32>,
+    current_radius: Option<f32>,
+    radius_velocity: f32,
 }
 
 impl Zoom {
@@ -121,10 +285,74 @@ impl Zoom {
             max,
             radius: (min + max) / 2.0,
             radius_copy: None,
+            current_radius: None,
+            radius_velocity: 0.0,
+        }
+    }
+
+    /// Distance the camera is at right now, on its way to `radius`
+    pub fn current_radius(&self) -> f32 {
+        self.current_radius.unwrap_or(self.radius)
+    }
+}
+
+/// Roughly how many seconds the camera takes to catch up with where it wants to be, with `0`
+/// snapping there every frame. Follow and zoom use a critically damped spring, so they settle
+/// without overshooting however the frames fall
+pub struct CameraSmoothing {
+    /// Following the target sideways
+    pub follow_horizontal: f32,
+    /// Following the target up and down
+    pub follow_vertical: f32,
+    /// Turning towards [`ThirdPersonCamera::orbit`]
+    pub rotation: f32,
+    /// Moving towards [`Zoom::radius`]
+    pub zoom: f32,
+    state: Option<SmoothedPose>,
+}
+
+#[derive(Clone, Copy)]
+struct SmoothedPose {
+    focus: Vec3,
+    focus_velocity: Vec3,
+    rotation: Quat,
+}
+
+impl Default for CameraSmoothing {
+    fn default() -> Self {
+        Self::new(0.0, 0.0, 0.0, 0.0)
+    }
+}
+
+impl CameraSmoothing {
+    pub fn new(follow_horizontal: f32, follow_vertical: f32, rotation: f32, zoom: f32) -> Self {
+        Self {
+            follow_horizontal,
+            follow_vertical,
+            rotation,
+            zoom,
+            state: None,
         }
     }
 }
 
+/// Moves `current` towards `target` like a critically damped spring that settles in about
+/// `smooth_time` seconds, keeping its speed in `velocity` between calls
+fn smooth_damp(current: f32, target: f32, velocity: &mut f32, smooth_time: f32, dt: f32) -> f32 {
+    if smooth_time <= 0.0 {
+        *velocity = 0.0;
+        return target;
+    }
+    let omega = 2.0 / smooth_time;
+    let x = omega * dt;
+    // approximates exp(-x) closely enough to stay stable at any frame time
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let spring = (*velocity + omega * change) * dt;
+    *velocity = (*velocity - omega * spring) * decay;
+    target + (change + spring) * decay
+}
+
 /// Offset the camera behind the player. For example, an offset value of (0.5, 0.25) will
 /// place the camera closer the player's right shoulder
 pub struct Offset {
@@ -143,6 +371,49 @@ impl Offset {
     }
 }
 
+/// Keeps the camera from going through colliders between it and its target, by sphere casting
+/// from the target towards where the camera wants to be
+pub struct CameraCollision {
+    pub enabled: bool,
+    /// Radius of the cast sphere, keeping the near plane out of walls
+    pub radius: f32,
+    /// Colliders outside these groups don't block the camera
+    pub groups: CollisionGroups,
+    /// Closest the camera is pulled towards its target
+    pub min_distance: f32,
+    /// How quickly the camera pulls in when blocked, per second
+    pub pull_in_speed: f32,
+    /// How quickly the camera eases back out once clear, per second
+    pub ease_out_speed: f32,
+    distance: Option<f32>,
+}
+
+impl Default for CameraCollision {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 0.2,
+            groups: CollisionGroups::default(),
+            min_distance: 0.5,
+            pull_in_speed: 25.0,
+            ease_out_speed: 3.0,
+            distance: None,
+        }
+    }
+}
+
+impl CameraCollision {
+    /// Enabled collision, casting a sphere of `radius` against colliders in `groups`
+    pub fn new(radius: f32, groups: CollisionGroups) -> Self {
+        Self {
+            enabled: true,
+            radius,
+            groups,
+            ..default()
+        }
+    }
+}
+
 #[derive(Resource)]
 pub struct GamepadResource(pub Gamepad);
 
@@ -152,19 +423,17 @@ pub struct GamepadResource(pub Gamepad);
 ///
 /// ```
 /// use bevy::prelude::*;
-/// use bevy_third_person_camera::{CustomGamepadSettings, ThirdPersonCamera};
+/// use bevy_third_person_camera::{CustomGamepadSettings, OrbitInputSettings, ThirdPersonCamera};
 /// fn spawn_camera(mut commands: Commands) {
-///    let gamepad = Gamepad::new(0);
 ///    commands.spawn((
 ///        ThirdPersonCamera {
 ///            gamepad_settings: CustomGamepadSettings {
-///                aim_button: GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2),
-///                mouse_orbit_button: GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger),
-///                offset_toggle_button: GamepadButton::new(gamepad, GamepadButtonType::DPadRight),
-///                x_sensitivity: 7.0,
-///                y_sensitivity: 4.0,
-///                zoom_in_button: GamepadButton::new(gamepad, GamepadButtonType::DPadUp),
-///                zoom_out_button: GamepadButton::new(gamepad, GamepadButtonType::DPadDown),
+///                aim_button: GamepadButtonType::LeftTrigger2,
+///                mouse_orbit_button: GamepadButtonType::LeftTrigger,
+///                offset_toggle_button: GamepadButtonType::DPadRight,
+///                orbit: OrbitInputSettings::new(7.0, 4.0),
+///                zoom_in_button: GamepadButtonType::DPadUp,
+///                zoom_out_button: GamepadButtonType::DPadDown,
 ///            },
 ///            ..default()
 ///        },
@@ -174,38 +443,35 @@ pub struct GamepadResource(pub Gamepad);
 /// ```
 #[derive(Component)]
 pub struct CustomGamepadSettings {
-    pub aim_button: GamepadButton,
-    pub mouse_orbit_button: GamepadButton,
-    pub offset_toggle_button: GamepadButton,
-    pub x_sensitivity: f32,
-    pub y_sensitivity: f32,
-    pub zoom_in_button: GamepadButton,
-    pub zoom_out_button: GamepadButton,
+    pub aim_button: GamepadButtonType,
+    pub mouse_orbit_button: GamepadButtonType,
+    pub offset_toggle_button: GamepadButtonType,
+    pub orbit: OrbitInputSettings,
+    pub zoom_in_button: GamepadButtonType,
+    pub zoom_out_button: GamepadButtonType,
 }
 
 impl Default for CustomGamepadSettings {
     fn default() -> Self {
-        let gamepad = Gamepad::new(0);
         Self {
-            aim_button: GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger2),
-            mouse_orbit_button: GamepadButton::new(gamepad, GamepadButtonType::LeftTrigger),
-            offset_toggle_button: GamepadButton::new(gamepad, GamepadButtonType::DPadRight),
-            x_sensitivity: 7.0,
-            y_sensitivity: 4.0,
-            zoom_in_button: GamepadButton::new(gamepad, GamepadButtonType::DPadUp),
-            zoom_out_button: GamepadButton::new(gamepad, GamepadButtonType::DPadDown),
+            aim_button: GamepadButtonType::LeftTrigger2,
+            mouse_orbit_button: GamepadButtonType::LeftTrigger,
+            offset_toggle_button: GamepadButtonType::DPadRight,
+            orbit: OrbitInputSettings::new(7.0, 4.0),
+            zoom_in_button: GamepadButtonType::DPadUp,
+            zoom_out_button: GamepadButtonType::DPadDown,
         }
     }
 }
 
 pub struct CustomTouchSettings {
-    pub touch_sensitivity: f32,
+    pub orbit: OrbitInputSettings,
 }
 
 impl Default for CustomTouchSettings {
     fn default() -> Self {
         Self {
-            touch_sensitivity: 0.05,
+            orbit: OrbitInputSettings::new(0.05, 0.05),
         }
     }
 }
@@ -228,84 +494,188 @@ impl Default for CustomTouchSettings {
 pub struct ThirdPersonCameraTarget;
 
 fn sync_player_camera(
-    player_q: Query<&Transform, With<ThirdPersonCameraTarget>>,
-    mut cam_q: Query<(&mut ThirdPersonCamera, &mut Transform), Without<ThirdPersonCameraTarget>>,
+    target_q: Query<Entity, With<ThirdPersonCameraTarget>>,
+    transform_q: Query<&Transform, Without<ThirdPersonCamera>>,
+    mut cam_q: Query<(&mut ThirdPersonCamera, &mut Transform)>,
+    rapier_ctx: Option<Res<RapierContext>>,
+    time: Res<Time>,
 ) {
-    let Ok(player) = player_q.get_single() else {
-        return;
-    };
-    let Ok((cam, mut cam_transform)) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    // Calculate the desired camera translation based on focus, radius, and xy_offset
-    let rotation_matrix = Mat3::from_quat(cam_transform.rotation);
+    let dt = time.delta_seconds();
+    for (mut cam, mut cam_transform) in cam_q.iter_mut() {
+        let Some(target) = cam.resolve_target(&target_q) else {
+            continue;
+        };
+        let Ok(player) = transform_q.get(target) else {
+            continue;
+        };
 
-    // apply the offset if offset_enabled is true
-    let mut offset = Vec3::ZERO;
-    if cam.offset_enabled {
-        offset = rotation_matrix.mul_vec3(Vec3::new(cam.offset.offset.0, cam.offset.offset.1, 0.0));
-    }
+        // ease the focus, rotation and radius towards where they want to be
+        cam.orbit = cam.orbit_limits.clamp(cam.orbit);
+        let target_rotation = cam.orbit.rotation();
+        let smoothing = &mut cam.smoothing;
+        let mut pose = smoothing.state.unwrap_or(SmoothedPose {
+            focus: player.translation,
+            focus_velocity: Vec3::ZERO,
+            rotation: target_rotation,
+        });
+        pose.focus = Vec3::new(
+            smooth_damp(
+                pose.focus.x,
+                player.translation.x,
+                &mut pose.focus_velocity.x,
+                smoothing.follow_horizontal,
+                dt,
+            ),
+            smooth_damp(
+                pose.focus.y,
+                player.translation.y,
+                &mut pose.focus_velocity.y,
+                smoothing.follow_vertical,
+                dt,
+            ),
+            smooth_damp(
+                pose.focus.z,
+                player.translation.z,
+                &mut pose.focus_velocity.z,
+                smoothing.follow_horizontal,
+                dt,
+            ),
+        );
+        pose.rotation = if smoothing.rotation > 0.0 {
+            pose.rotation
+                .slerp(target_rotation, 1.0 - (-dt / smoothing.rotation).exp())
+        } else {
+            target_rotation
+        };
+        smoothing.state = Some(pose);
+
+        let zoom_time = smoothing.zoom;
+        let zoom = &mut cam.zoom;
+        let current_radius = zoom.current_radius.unwrap_or(zoom.radius);
+        zoom.current_radius = Some(smooth_damp(
+            current_radius,
+            zoom.radius,
+            &mut zoom.radius_velocity,
+            zoom_time,
+            dt,
+        ));
+
+        // Calculate the desired camera translation based on focus, radius, and xy_offset
+        cam_transform.rotation = pose.rotation;
+        let rotation_matrix = Mat3::from_quat(pose.rotation);
+
+        // apply the offset if offset_enabled is true
+        let mut offset = Vec3::ZERO;
+        if cam.offset_enabled {
+            offset =
+                rotation_matrix.mul_vec3(Vec3::new(cam.offset.offset.0, cam.offset.offset.1, 0.0));
+        }
 
-    let desired_translation =
-        cam.focus + rotation_matrix.mul_vec3(Vec3::new(0.0, 0.0, cam.zoom.radius)) + offset;
+        let desired_translation = cam.focus
+            + rotation_matrix.mul_vec3(Vec3::new(0.0, 0.0, cam.zoom.current_radius()))
+            + offset;
+
+        // the arm from the target to the camera, shortened by anything in the way
+        let arm = desired_translation - cam.focus;
+        let length = arm.length();
+        let distance = match rapier_ctx.as_deref() {
+            Some(rapier_ctx) if cam.collision.enabled && length > f32::EPSILON => {
+                let collision = &cam.collision;
+                let filter = QueryFilter::exclude_dynamic()
+                    .exclude_sensors()
+                    .groups(collision.groups)
+                    .exclude_rigid_body(target);
+                // cast from the target itself, since the smoothed focus can trail it into the
+                // floor, e.g. on takeoff
+                let allowed = rapier_ctx
+                    .cast_shape(
+                        player.translation,
+                        Quat::IDENTITY,
+                        arm / length,
+                        &Collider::ball(collision.radius),
+                        length,
+                        filter,
+                    )
+                    .map_or(length, |(_, toi)| toi.toi)
+                    .max(collision.min_distance.min(length));
+
+                let current = collision.distance.unwrap_or(length).min(length);
+                let speed = if allowed < current {
+                    collision.pull_in_speed
+                } else {
+                    collision.ease_out_speed
+                };
+                let distance =
+                    allowed + (current - allowed) * (-speed * time.delta_seconds()).exp();
+                cam.collision.distance = Some(distance);
+                distance
+            }
+            _ => {
+                cam.collision.distance = None;
+                length
+            }
+        };
 
-    // Update the camera translation and focus
-    let delta = player.translation - cam.focus;
-    cam_transform.translation = desired_translation + delta;
+        // Update the camera translation and focus
+        cam_transform.translation = pose.focus + arm.normalize_or_zero() * distance;
+    }
 }
 
-// only run aiming logic if `aim_enabled` is true
-fn aim_condition(cam_q: Query<&ThirdPersonCamera, With<ThirdPersonCamera>>) -> bool {
-    let Ok(cam) = cam_q.get_single() else {
-        return false;
-    };
-    cam.aim_enabled
+// only run aiming logic if `aim_enabled` is true for some camera
+fn aim_condition(cam_q: Query<&ThirdPersonCamera>) -> bool {
+    cam_q.iter().any(|cam| cam.aim_enabled)
 }
 
 fn aim(
-    mut cam_q: Query<
-        (&mut ThirdPersonCamera, &Transform),
-        (With<ThirdPersonCamera>, Without<ThirdPersonCameraTarget>),
-    >,
+    mut cam_q: Query<(&mut ThirdPersonCamera, &Transform)>,
+    target_q: Query<Entity, With<ThirdPersonCameraTarget>>,
+    mut transform_q: Query<&mut Transform, Without<ThirdPersonCamera>>,
     mouse: Res<Input<MouseButton>>,
-    mut player_q: Query<&mut Transform, With<ThirdPersonCameraTarget>>,
     btns: Res<Input<GamepadButton>>,
+    gamepad_res: Option<Res<GamepadResource>>,
     time: Res<Time>,
 ) {
-    let Ok((mut cam, cam_transform)) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    // check if aim button was pressed
-    let aim_btn = mouse.pressed(cam.aim_button) || btns.pressed(cam.gamepad_settings.aim_button);
-
-    if aim_btn {
-        // rotate player or target to face direction he is aiming
-        let Ok(mut player_transform) = player_q.get_single_mut() else {
-            return;
-        };
-        player_transform.look_to(cam_transform.forward(), Vec3::Y);
-
-        let desired_zoom = cam.zoom.min * cam.aim_zoom;
-
-        // radius_copy is used for restoring the radius (zoom) to it's
-        // original value after releasing the aim button
-        if cam.zoom.radius_copy.is_none() {
-            cam.zoom.radius_copy = Some(cam.zoom.radius);
+    for (mut cam, cam_transform) in cam_q.iter_mut() {
+        if !cam.aim_enabled {
+            continue;
         }
 
-        let zoom_factor =
-            (cam.zoom.radius_copy.unwrap() / cam.aim_zoom) * cam.aim_speed * time.delta_seconds();
+        // check if aim button was pressed
+        let gamepad = cam.input.gamepad(gamepad_res.as_deref());
+        let aim_btn = (cam.input.mouse && mouse.pressed(cam.aim_button))
+            || gamepad.is_some_and(|gamepad| {
+                btns.pressed(GamepadButton::new(gamepad, cam.gamepad_settings.aim_button))
+            });
+
+        if aim_btn {
+            // rotate player or target to face direction he is aiming
+            let Some(mut player_transform) = cam
+                .resolve_target(&target_q)
+                .and_then(|target| transform_q.get_mut(target).ok())
+            else {
+                continue;
+            };
+            player_transform.look_to(cam_transform.forward(), Vec3::Y);
+
+            let desired_zoom = cam.zoom.min * cam.aim_zoom;
+
+            // radius_copy is used for restoring the radius (zoom) to it's
+            // original value after releasing the aim button
+            if cam.zoom.radius_copy.is_none() {
+                cam.zoom.radius_copy = Some(cam.zoom.radius);
+            }
 
-        // stop zooming in if current radius is less than desired zoom
-        if cam.zoom.radius <= desired_zoom || cam.zoom.radius - zoom_factor <= desired_zoom {
-            cam.zoom.radius = desired_zoom;
-        } else {
-            cam.zoom.radius -= zoom_factor;
-        }
-    } else {
-        if let Some(radius_copy) = cam.zoom.radius_copy {
+            let zoom_factor = (cam.zoom.radius_copy.unwrap() / cam.aim_zoom)
+                * cam.aim_speed
+                * time.delta_seconds();
+
+            // stop zooming in if current radius is less than desired zoom
+            if cam.zoom.radius <= desired_zoom || cam.zoom.radius - zoom_factor <= desired_zoom {
+                cam.zoom.radius = desired_zoom;
+            } else {
+                cam.zoom.radius -= zoom_factor;
+            }
+        } else if let Some(radius_copy) = cam.zoom.radius_copy {
             let zoom_factor = (radius_copy / cam.aim_zoom) * cam.aim_speed * time.delta_seconds();
 
             // stop zooming out if current radius is greater than original radius
@@ -313,75 +683,84 @@ fn aim(
                 cam.zoom.radius = radius_copy;
                 cam.zoom.radius_copy = None;
             } else {
-                cam.zoom.radius +=
-                    (radius_copy / cam.aim_zoom) * cam.aim_speed * time.delta_seconds();
+                cam.zoom.radius += zoom_factor;
             }
         }
     }
 }
 
-pub fn zoom_condition(cam_q: Query<&ThirdPersonCamera, With<ThirdPersonCamera>>) -> bool {
-    let Ok(cam) = cam_q.get_single() else {
-        return false;
-    };
-    cam.zoom_enabled
+pub fn zoom_condition(cam_q: Query<&ThirdPersonCamera>) -> bool {
+    cam_q.iter().any(|cam| cam.zoom_enabled)
 }
 
-// only run toggle_x_offset if `offset_toggle_enabled` is true
-fn toggle_x_offset_condition(cam_q: Query<&ThirdPersonCamera, With<ThirdPersonCamera>>) -> bool {
-    let Ok(cam) = cam_q.get_single() else {
-        return false;
-    };
-    cam.offset_toggle_enabled
+// only run toggle_x_offset if `offset_toggle_enabled` is true for some camera
+fn toggle_x_offset_condition(cam_q: Query<&ThirdPersonCamera>) -> bool {
+    cam_q.iter().any(|cam| cam.offset_toggle_enabled)
 }
 
 // inverts the x offset. Example: left shoulder view -> right shoulder view & vice versa
 fn toggle_x_offset(
-    mut cam_q: Query<&mut ThirdPersonCamera, With<ThirdPersonCamera>>,
+    mut cam_q: Query<&mut ThirdPersonCamera>,
     keys: Res<Input<KeyCode>>,
     time: Res<Time>,
     btns: Res<Input<GamepadButton>>,
+    gamepad_res: Option<Res<GamepadResource>>,
 ) {
-    let Ok(mut cam) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    // check if toggle btn was pressed
-    let toggle_btn = keys.just_pressed(cam.offset_toggle_key)
-        || btns.just_pressed(cam.gamepad_settings.offset_toggle_button);
+    for mut cam in cam_q.iter_mut() {
+        if !cam.offset_toggle_enabled {
+            continue;
+        }
 
-    if toggle_btn {
-        // Switch direction by inverting the offset_flag
-        cam.offset.is_transitioning = !cam.offset.is_transitioning;
-    }
+        // check if toggle btn was pressed
+        let gamepad = cam.input.gamepad(gamepad_res.as_deref());
+        let toggle_btn = (cam.input.mouse && keys.just_pressed(cam.offset_toggle_key))
+            || gamepad.is_some_and(|gamepad| {
+                btns.just_pressed(GamepadButton::new(
+                    gamepad,
+                    cam.gamepad_settings.offset_toggle_button,
+                ))
+            });
+
+        if toggle_btn {
+            // Switch direction by inverting the offset_flag
+            cam.offset.is_transitioning = !cam.offset.is_transitioning;
+        }
 
-    // Determine the transition speed based on direction
-    let transition_speed = if cam.offset.is_transitioning {
-        -cam.offset_toggle_speed
-    } else {
-        cam.offset_toggle_speed
-    };
+        // Determine the transition speed based on direction
+        let transition_speed = if cam.offset.is_transitioning {
+            -cam.offset_toggle_speed
+        } else {
+            cam.offset_toggle_speed
+        };
 
-    // Update the offset based on the direction and time
-    cam.offset.offset.0 = (cam.offset.offset.0 + transition_speed * time.delta_seconds())
-        .clamp(-cam.offset.offset_copy.0, cam.offset.offset_copy.0);
+        // Update the offset based on the direction and time
+        cam.offset.offset.0 = (cam.offset.offset.0 + transition_speed * time.delta_seconds())
+            .clamp(-cam.offset.offset_copy.0, cam.offset.offset_copy.0);
+    }
 }
 
+// the cursor is locked while any camera driven by the mouse wants it to be
 fn toggle_cursor(
     mut cam_q: Query<&mut ThirdPersonCamera>,
     keys: Res<Input<KeyCode>>,
     mut window_q: Query<&mut Window, With<PrimaryWindow>>,
 ) {
-    let Ok(mut cam) = cam_q.get_single_mut() else {
+    let mut lock = None;
+    for mut cam in cam_q.iter_mut() {
+        if !cam.input.mouse || !cam.cursor_lock_toggle_enabled {
+            continue;
+        }
+        if keys.just_pressed(cam.cursor_lock_key) {
+            cam.cursor_lock_active = !cam.cursor_lock_active;
+        }
+        lock = Some(lock.unwrap_or(false) || cam.cursor_lock_active);
+    }
+    let Some(lock) = lock else {
         return;
     };
 
-    if keys.just_pressed(cam.cursor_lock_key) {
-        cam.cursor_lock_active = !cam.cursor_lock_active;
-    }
-
     let mut window = window_q.get_single_mut().unwrap();
-    if cam.cursor_lock_active {
+    if lock {
         window.cursor.grab_mode = CursorGrabMode::Locked;
         window.cursor.visible = false;
     } else {
@@ -392,8 +771,61 @@ fn toggle_cursor(
 
 // checks if the toggle cursor functionality is enabled
 fn toggle_cursor_condition(cam_q: Query<&ThirdPersonCamera>) -> bool {
-    let Ok(cam) = cam_q.get_single() else {
-        return true;
-    };
-    cam.cursor_lock_toggle_enabled
+    cam_q.iter().any(|cam| cam.cursor_lock_toggle_enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_keeps_pitch_within_bounds() {
+        let limits = OrbitLimits::default();
+        assert_eq!(limits.clamp(Orbit::new(0.0, 3.0)).pitch, limits.max_pitch);
+        assert_eq!(limits.clamp(Orbit::new(0.0, -3.0)).pitch, limits.min_pitch);
+        assert_eq!(limits.clamp(Orbit::new(0.0, 0.3)).pitch, 0.3);
+    }
+
+    #[test]
+    fn clamp_wraps_unlimited_yaw() {
+        let limits = OrbitLimits::default();
+        for yaw in [0.5, 0.5 + TAU, 0.5 - TAU, 0.5 + 5.0 * TAU] {
+            let clamped = limits.clamp(Orbit::new(yaw, 0.0)).yaw;
+            assert!((clamped - 0.5).abs() < 1e-4, "{yaw} wrapped to {clamped}");
+        }
+        let wrapped = limits.clamp(Orbit::new(PI + 0.25, 0.0)).yaw;
+        assert!((wrapped - (0.25 - PI)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn clamp_keeps_limited_yaw_within_bounds() {
+        let limits = OrbitLimits {
+            yaw: Some((-1.0, 1.0)),
+            ..default()
+        };
+        assert_eq!(limits.clamp(Orbit::new(2.0, 0.0)).yaw, 1.0);
+        assert_eq!(limits.clamp(Orbit::new(-2.0 - TAU, 0.0)).yaw, -1.0);
+        assert_eq!(limits.clamp(Orbit::new(0.5, 0.0)).yaw, 0.5);
+    }
+
+    #[test]
+    fn rotation_round_trips() {
+        for (yaw, pitch) in [(0.0, 0.0), (1.2, -0.4), (-2.5, 0.9), (3.0, -1.4)] {
+            let orbit = Orbit::new(yaw, pitch);
+            let back = Orbit::from_rotation(orbit.rotation());
+            assert!((back.yaw - yaw).abs() < 1e-4, "{orbit:?} became {back:?}");
+            assert!(
+                (back.pitch - pitch).abs() < 1e-4,
+                "{orbit:?} became {back:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn from_rotation_ignores_roll() {
+        let rotation = Quat::from_euler(EulerRot::YXZ, 0.7, -0.3, 0.5);
+        let orbit = Orbit::from_rotation(rotation);
+        assert!((orbit.yaw - 0.7).abs() < 1e-4);
+        assert!((orbit.pitch + 0.3).abs() < 1e-4);
+    }
 }
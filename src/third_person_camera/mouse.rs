@@ -1,4 +1,4 @@
-use crate::{zoom_condition, ThirdPersonCamera};
+use crate::{zoom_condition, CameraOrbitInput, CameraOrbitInputSet, ThirdPersonCamera};
 use bevy::{
     input::mouse::{MouseMotion, MouseWheel},
     prelude::*,
@@ -13,7 +13,7 @@ impl Plugin for MousePlugin {
         app.add_systems(
             Update,
             (
-                orbit_mouse.run_if(orbit_condition),
+                orbit_mouse.in_set(CameraOrbitInputSet),
                 zoom_mouse.run_if(zoom_condition),
             )
                 .chain(),
@@ -21,65 +21,38 @@ impl Plugin for MousePlugin {
     }
 }
 
-// only run the orbit system if the cursor lock is disabled
-fn orbit_condition(cam_q: Query<&ThirdPersonCamera>) -> bool {
-    let Ok(cam) = cam_q.get_single() else {
-        return true;
-    };
-    return cam.cursor_lock_active;
-}
-
 // heavily referenced https://bevy-cheatbook.github.io/cookbook/pan-orbit-camera.html
 pub fn orbit_mouse(
     window_q: Query<&Window, With<PrimaryWindow>>,
-    mut cam_q: Query<(&ThirdPersonCamera, &mut Transform), With<ThirdPersonCamera>>,
+    cam_q: Query<(Entity, &ThirdPersonCamera)>,
     mouse: Res<Input<MouseButton>>,
     mut mouse_evr: EventReader<MouseMotion>,
+    mut orbit_writer: EventWriter<CameraOrbitInput>,
 ) {
-    let mut rotation = Vec2::ZERO;
-    for ev in mouse_evr.iter() {
-        rotation = ev.delta;
-    }
-
-    let Ok((cam, mut cam_transform)) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    if cam.mouse_orbit_button_enabled && !mouse.pressed(cam.mouse_orbit_button) {
-        return;
-    }
-
-    if !cam.orbit_enabled {
+    // several motion events can arrive in one frame
+    let rotation = mouse_evr.iter().fold(Vec2::ZERO, |sum, ev| sum + ev.delta);
+    if rotation.length_squared() == 0.0 {
         return;
     }
 
-    rotation *= cam.mouse_sensitivity;
+    let window = window_q.get_single().unwrap();
+    let delta = Vec2::new(rotation.x / window.width(), rotation.y / window.height()) * PI;
 
-    if rotation.length_squared() > 0.0 {
-        let window = window_q.get_single().unwrap();
-        let delta_x = {
-            let delta = rotation.x / window.width() * std::f32::consts::PI;
-            delta
-        };
-
-        let delta_y = rotation.y / window.height() * PI;
-        let yaw = Quat::from_rotation_y(-delta_x);
-        let pitch = Quat::from_rotation_x(-delta_y);
-        cam_transform.rotation = yaw * cam_transform.rotation; // rotate around global y axis
-
-        // Calculate the new rotation without applying it to the camera yet
-        let new_rotation = cam_transform.rotation * pitch;
+    for (entity, cam) in cam_q.iter() {
+        // the mouse only orbits while the cursor is locked
+        if !cam.input.mouse || !cam.cursor_lock_active || !cam.orbit_enabled {
+            continue;
+        }
 
-        // check if new rotation will cause camera to go beyond the 180 degree vertical bounds
-        let up_vector = new_rotation * Vec3::Y;
-        if up_vector.y > 0.0 {
-            cam_transform.rotation = new_rotation;
+        if cam.mouse_orbit_button_enabled && !mouse.pressed(cam.mouse_orbit_button) {
+            continue;
         }
-    }
 
-    let rot_matrix = Mat3::from_quat(cam_transform.rotation);
-    cam_transform.translation =
-        cam.focus + rot_matrix.mul_vec3(Vec3::new(0.0, 0.0, cam.zoom.radius));
+        orbit_writer.send(CameraOrbitInput {
+            delta: cam.mouse_orbit.apply(delta),
+            camera: Some(entity),
+        });
+    }
 }
 
 fn zoom_mouse(mut scroll_evr: EventReader<MouseWheel>, mut cam_q: Query<&mut ThirdPersonCamera>) {
@@ -88,11 +61,15 @@ fn zoom_mouse(mut scroll_evr: EventReader<MouseWheel>, mut cam_q: Query<&mut Thi
         scroll += ev.y;
     }
 
-    if let Ok(mut cam) = cam_q.get_single_mut() {
-        if scroll.abs() > 0.0 {
-            let new_radius =
-                cam.zoom.radius - scroll * cam.zoom.radius * 0.1 * cam.zoom_sensitivity;
-            cam.zoom.radius = new_radius.clamp(cam.zoom.min, cam.zoom.max);
+    if scroll.abs() == 0.0 {
+        return;
+    }
+
+    for mut cam in cam_q.iter_mut() {
+        if !cam.input.mouse || !cam.zoom_enabled {
+            continue;
         }
+        let new_radius = cam.zoom.radius - scroll * cam.zoom.radius * 0.1 * cam.zoom_sensitivity;
+        cam.zoom.radius = new_radius.clamp(cam.zoom.min, cam.zoom.max);
     }
 }
@@ -0,0 +1,92 @@
+use crate::ThirdPersonCamera;
+use bevy::prelude::*;
+
+pub struct OrbitPlugin;
+
+impl Plugin for OrbitPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CameraOrbitInput>().add_systems(
+            Update,
+            apply_orbit_input
+                .in_set(CameraOrbitSet)
+                .after(CameraOrbitInputSet),
+        );
+    }
+}
+
+/// Systems that send [`CameraOrbitInput`]. Custom sources should run in it, so their input is
+/// applied the same frame
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraOrbitInputSet;
+
+/// The system that turns cameras by the [`CameraOrbitInput`] sent this frame
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CameraOrbitSet;
+
+/// Turns a [`ThirdPersonCamera`]'s orbit, in radians. `x` turns the view right and `y` tilts
+/// it down.
+///
+/// Sent by the mouse, gamepad and touch adapters, each after applying its own
+/// [`OrbitInputSettings`]. Anything else that wants to turn the camera, like a cinematic, can
+/// send it too. Everything sent in a frame is added up
+#[derive(Event, Clone, Copy, Debug, Default)]
+pub struct CameraOrbitInput {
+    pub delta: Vec2,
+    /// Camera to turn, or `None` to turn all of them
+    pub camera: Option<Entity>,
+}
+
+/// How an input device's raw movement becomes [`CameraOrbitInput`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrbitInputSettings {
+    /// Multiplies the horizontal and vertical movement
+    pub sensitivity: Vec2,
+    pub invert_x: bool,
+    pub invert_y: bool,
+    /// Extra turn per radian turned, so quick flicks go further than slow drags. `0` is linear
+    pub acceleration: f32,
+}
+
+impl Default for OrbitInputSettings {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+impl OrbitInputSettings {
+    pub fn new(x_sensitivity: f32, y_sensitivity: f32) -> Self {
+        Self {
+            sensitivity: Vec2::new(x_sensitivity, y_sensitivity),
+            invert_x: false,
+            invert_y: false,
+            acceleration: 0.0,
+        }
+    }
+
+    /// The orbit input for `delta` radians of raw movement
+    pub fn apply(&self, delta: Vec2) -> Vec2 {
+        let scaled = delta * self.sensitivity;
+        let accelerated = scaled * (1.0 + self.acceleration * scaled.length());
+        let sign = |invert: bool| if invert { -1.0 } else { 1.0 };
+        accelerated * Vec2::new(sign(self.invert_x), sign(self.invert_y))
+    }
+}
+
+fn apply_orbit_input(
+    mut orbit_reader: EventReader<CameraOrbitInput>,
+    mut cam_q: Query<(Entity, &mut ThirdPersonCamera)>,
+) {
+    let inputs: Vec<CameraOrbitInput> = orbit_reader.iter().copied().collect();
+    if inputs.is_empty() {
+        return;
+    }
+    for (entity, mut cam) in cam_q.iter_mut() {
+        let delta = inputs
+            .iter()
+            .filter(|input| input.camera.is_none_or(|camera| camera == entity))
+            .fold(Vec2::ZERO, |sum, input| sum + input.delta);
+        if delta != Vec2::ZERO {
+            cam.rotate(-delta.x, -delta.y);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::ThirdPersonCamera;
+use crate::{CameraOrbitInput, CameraOrbitInputSet, ThirdPersonCamera};
 use bevy::{prelude::*, window::PrimaryWindow};
 use std::f32::consts::PI;
 
@@ -6,53 +6,42 @@ pub struct TouchPlugin;
 
 impl Plugin for TouchPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (orbit_touch).chain());
+        app.add_systems(Update, orbit_touch.in_set(CameraOrbitInputSet));
     }
 }
 
 pub fn orbit_touch(
     window_q: Query<&Window, With<PrimaryWindow>>,
-    mut cam_q: Query<(&ThirdPersonCamera, &mut Transform), With<ThirdPersonCamera>>,
+    cam_q: Query<(Entity, &ThirdPersonCamera)>,
     touches: Res<Touches>,
+    mut orbit_writer: EventWriter<CameraOrbitInput>,
 ) {
-    let mut rotation = Vec2::ZERO;
-    if let Some(touch) = touches.iter().next() {
-        rotation = touch.position() - touch.start_position();
-    }
-
-    let Ok((cam, mut cam_transform)) = cam_q.get_single_mut() else {
-        return;
-    };
-
-    if !cam.orbit_enabled {
+    if touches.iter().next().is_none() {
         return;
     }
+    let window = window_q.get_single().unwrap();
+    let size = Vec2::new(window.width(), window.height());
 
-    rotation *= cam.touch_settings.touch_sensitivity;
+    for (entity, cam) in cam_q.iter() {
+        if !cam.orbit_enabled {
+            continue;
+        }
 
-    if rotation.length_squared() > 0.0 {
-        let window = window_q.get_single().unwrap();
-        let delta_x = {
-            let delta = rotation.x / window.width() * PI;
-            delta
+        // the first touch that started in the camera's part of the screen
+        let Some(touch) = touches
+            .iter()
+            .find(|touch| cam.input.owns_touch(touch.start_position(), size))
+        else {
+            continue;
         };
-
-        let delta_y = rotation.y / window.height() * PI;
-        let yaw = Quat::from_rotation_y(-delta_x);
-        let pitch = Quat::from_rotation_x(-delta_y);
-        cam_transform.rotation = yaw * cam_transform.rotation; // rotate around global y axis
-
-        // Calculate the new rotation without applying it to the camera yet
-        let new_rotation = cam_transform.rotation * pitch;
-
-        // check if new rotation will cause camera to go beyond the 180 degree vertical bounds
-        let up_vector = new_rotation * Vec3::Y;
-        if up_vector.y > 0.0 {
-            cam_transform.rotation = new_rotation;
+        let rotation = touch.position() - touch.start_position();
+
+        if rotation.length_squared() > 0.0 {
+            let delta = rotation / size * PI;
+            orbit_writer.send(CameraOrbitInput {
+                delta: cam.touch_settings.orbit.apply(delta),
+                camera: Some(entity),
+            });
         }
     }
-
-    let rot_matrix = Mat3::from_quat(cam_transform.rotation);
-    cam_transform.translation =
-        cam.focus + rot_matrix.mul_vec3(Vec3::new(0.0, 0.0, cam.zoom.radius));
 }
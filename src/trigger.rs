@@ -0,0 +1,325 @@
+use crate::physics::FixedStepSet;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+pub struct TriggerPlugin;
+
+impl Plugin for TriggerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TriggerEntered>()
+            .add_event::<TriggerExited>()
+            .add_systems(
+                FixedUpdate,
+                update_trigger_volumes
+                    .in_set(TriggerSet)
+                    .in_set(FixedStepSet::Record),
+            );
+    }
+}
+
+/// Systems that read [`TriggerEntered`] or [`TriggerExited`] in the same tick should run after
+/// this set
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TriggerSet;
+
+/// Reports bodies overlapping this entity's [`Collider`] through [`TriggerEntered`] and
+/// [`TriggerExited`].
+///
+/// The collider can have any shape and is usually a [`Sensor`]. Fixed bodies and other sensors
+/// are never reported.
+#[derive(Component, Clone, Debug, Default)]
+pub struct TriggerVolume {
+    /// Only bodies with one of these [`TriggerTags`] are reported. Empty for any body
+    pub tags: Vec<String>,
+    /// Stop reporting after the first entry
+    pub one_shot: bool,
+    /// Seconds after an entry during which new entries are ignored. Bodies ignored this way are
+    /// reported once the cooldown is over if they are still inside
+    pub cooldown: f32,
+}
+
+impl TriggerVolume {
+    /// A trigger that only reports bodies tagged with `tag`
+    pub fn tagged(tag: impl Into<String>) -> Self {
+        Self {
+            tags: vec![tag.into()],
+            ..default()
+        }
+    }
+}
+
+/// Tags matched against [`TriggerVolume::tags`]
+#[derive(Component, Clone, Debug, Default)]
+pub struct TriggerTags(pub Vec<String>);
+
+impl TriggerTags {
+    pub fn new(tags: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self(tags.into_iter().map(Into::into).collect())
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.iter().any(|own| own == tag)
+    }
+}
+
+/// Bodies currently inside a [`TriggerVolume`], added to it on the first tick
+#[derive(Component, Default)]
+pub struct TriggerContents {
+    inside: Vec<Entity>,
+    cooldown_remaining: f32,
+    spent: bool,
+}
+
+impl TriggerContents {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.inside.contains(&entity)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.inside.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inside.is_empty()
+    }
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TriggerEntered {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TriggerExited {
+    pub trigger: Entity,
+    pub entity: Entity,
+}
+
+#[allow(clippy::type_complexity)]
+fn update_trigger_volumes(
+    mut commands: Commands,
+    mut trigger_query: Query<(
+        Entity,
+        &TriggerVolume,
+        &Collider,
+        &GlobalTransform,
+        Option<&mut TriggerContents>,
+    )>,
+    tags_query: Query<&TriggerTags>,
+    rapier_ctx: Res<RapierContext>,
+    fixed_time: Res<FixedTime>,
+    mut entered_writer: EventWriter<TriggerEntered>,
+    mut exited_writer: EventWriter<TriggerExited>,
+) {
+    let dt = fixed_time.period.as_secs_f32();
+
+    for (trigger, volume, collider, transform, contents) in trigger_query.iter_mut() {
+        let Some(mut contents) = contents else {
+            commands.entity(trigger).insert(TriggerContents::default());
+            continue;
+        };
+        contents.cooldown_remaining = (contents.cooldown_remaining - dt).max(0.0);
+
+        // a body with several colliders is reported once, as the body
+        let mut overlapping = Vec::new();
+        let (_, rotation, translation) = transform.to_scale_rotation_translation();
+        rapier_ctx.intersections_with_shape(
+            translation,
+            rotation,
+            collider,
+            QueryFilter::exclude_fixed()
+                .exclude_sensors()
+                .exclude_collider(trigger),
+            |other| {
+                let body = rapier_ctx.collider_parent(other).unwrap_or(other);
+                let tagged = volume.tags.is_empty()
+                    || tags_query
+                        .get(body)
+                        .is_ok_and(|tags| volume.tags.iter().any(|tag| tags.contains(tag)));
+                if tagged && !overlapping.contains(&body) {
+                    overlapping.push(body);
+                }
+                true
+            },
+        );
+
+        let mut index = 0;
+        while index < contents.inside.len() {
+            let entity = contents.inside[index];
+            if overlapping.contains(&entity) {
+                index += 1;
+                continue;
+            }
+            contents.inside.swap_remove(index);
+            exited_writer.send(TriggerExited { trigger, entity });
+        }
+
+        for entity in overlapping {
+            if contents.inside.contains(&entity)
+                || contents.spent
+                || contents.cooldown_remaining > 0.0
+            {
+                continue;
+            }
+            contents.inside.push(entity);
+            contents.cooldown_remaining = volume.cooldown;
+            contents.spent = volume.one_shot;
+            entered_writer.send(TriggerEntered { trigger, entity });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::{PhysicsPlugin, PHYSICS_TIMESTEP};
+    use bevy::{scene::SceneSpawner, time::TimeUpdateStrategy};
+
+    const INSIDE: Vec3 = Vec3::ZERO;
+    const OUTSIDE: Vec3 = Vec3::new(10.0, 0.0, 0.0);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            HierarchyPlugin,
+        ))
+        .add_asset::<Mesh>()
+        .add_asset::<Scene>()
+        .init_resource::<SceneSpawner>()
+        // ticks are run by hand, so frames only propagate transforms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(default()))
+        .add_plugins((PhysicsPlugin, TriggerPlugin));
+        app.update();
+        app
+    }
+
+    fn spawn_trigger(app: &mut App, volume: TriggerVolume) -> Entity {
+        app.world
+            .spawn((
+                TransformBundle::default(),
+                Collider::cuboid(1.0, 1.0, 1.0),
+                Sensor,
+                volume,
+            ))
+            .id()
+    }
+
+    fn spawn_body(app: &mut App, tags: &[&str]) -> Entity {
+        app.world
+            .spawn((
+                TransformBundle::from_transform(Transform::from_translation(OUTSIDE)),
+                RigidBody::KinematicPositionBased,
+                Collider::ball(0.25),
+                TriggerTags::new(tags.iter().copied()),
+            ))
+            .id()
+    }
+
+    /// Moves `body` to `position` and runs a tick, returning the bodies that entered and exited
+    fn tick(app: &mut App, body: Entity, position: Vec3) -> (Vec<Entity>, Vec<Entity>) {
+        app.world.get_mut::<Transform>(body).unwrap().translation = position;
+        app.update();
+        app.world.run_schedule(FixedUpdate);
+        let entered = app
+            .world
+            .resource_mut::<Events<TriggerEntered>>()
+            .drain()
+            .map(|entered| entered.entity)
+            .collect();
+        let exited = app
+            .world
+            .resource_mut::<Events<TriggerExited>>()
+            .drain()
+            .map(|exited| exited.entity)
+            .collect();
+        (entered, exited)
+    }
+
+    #[test]
+    fn reports_entering_and_exiting_once() {
+        let mut app = app();
+        let trigger = spawn_trigger(&mut app, TriggerVolume::default());
+        let body = spawn_body(&mut app, &[]);
+        tick(&mut app, body, OUTSIDE);
+
+        assert_eq!(tick(&mut app, body, INSIDE), (vec![body], vec![]));
+        assert_eq!(tick(&mut app, body, INSIDE), (vec![], vec![]));
+        assert!(app
+            .world
+            .get::<TriggerContents>(trigger)
+            .unwrap()
+            .contains(body));
+        assert_eq!(tick(&mut app, body, OUTSIDE), (vec![], vec![body]));
+        assert!(app
+            .world
+            .get::<TriggerContents>(trigger)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn ignores_untagged_bodies() {
+        let mut app = app();
+        spawn_trigger(&mut app, TriggerVolume::tagged("player"));
+        let other = spawn_body(&mut app, &["crate"]);
+        tick(&mut app, other, OUTSIDE);
+        assert_eq!(tick(&mut app, other, INSIDE), (vec![], vec![]));
+
+        let player = spawn_body(&mut app, &["player"]);
+        tick(&mut app, player, OUTSIDE);
+        assert_eq!(tick(&mut app, player, INSIDE), (vec![player], vec![]));
+    }
+
+    #[test]
+    fn one_shot_reports_only_the_first_entry() {
+        let mut app = app();
+        spawn_trigger(
+            &mut app,
+            TriggerVolume {
+                one_shot: true,
+                ..default()
+            },
+        );
+        let body = spawn_body(&mut app, &[]);
+        tick(&mut app, body, OUTSIDE);
+
+        assert_eq!(tick(&mut app, body, INSIDE), (vec![body], vec![]));
+        assert_eq!(tick(&mut app, body, OUTSIDE), (vec![], vec![body]));
+        assert_eq!(tick(&mut app, body, INSIDE), (vec![], vec![]));
+    }
+
+    #[test]
+    fn cooldown_delays_entries_until_it_is_over() {
+        let cooldown = 0.5;
+        let mut app = app();
+        spawn_trigger(
+            &mut app,
+            TriggerVolume {
+                cooldown,
+                ..default()
+            },
+        );
+        let body = spawn_body(&mut app, &[]);
+        tick(&mut app, body, OUTSIDE);
+
+        assert_eq!(tick(&mut app, body, INSIDE), (vec![body], vec![]));
+        assert_eq!(tick(&mut app, body, OUTSIDE), (vec![], vec![body]));
+
+        // back inside during the cooldown, and reported once it's over
+        let cooldown_ticks = (cooldown / PHYSICS_TIMESTEP).round() as usize;
+        let mut entered_on = None;
+        for index in 0..cooldown_ticks * 2 {
+            let (entered, _) = tick(&mut app, body, INSIDE);
+            if entered == vec![body] {
+                entered_on = Some(index);
+                break;
+            }
+        }
+        let entered_on = entered_on.expect("entry reported after the cooldown");
+        assert!(entered_on >= cooldown_ticks - 2);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::goal::{LevelCompleted, RunState, RunTimer};
+use crate::level::{LevelSpawned, LoadLevel};
+use crate::third_person_camera::ThirdPersonCamera;
+use crate::ui::PersistentUi;
+use bevy::prelude::*;
+
+const BUTTON_COLOR: Color = Color::DARK_GRAY;
+const BUTTON_HOVERED_COLOR: Color = Color::GRAY;
+
+pub struct CompletionPlugin;
+
+impl Plugin for CompletionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, (create_run_timer_text, create_completion_screen))
+            .add_systems(
+                Update,
+                (
+                    show_run_timer,
+                    hide_completion_screen_on_level_spawned,
+                    show_completion_screen,
+                    press_completion_buttons,
+                ),
+            );
+    }
+}
+
+/// Shows the [`RunTimer`] while a run is going
+#[derive(Component)]
+pub struct RunTimerText;
+
+/// Shown once a level is completed, with the run's results
+#[derive(Component, Default)]
+pub struct CompletionScreen {
+    level: String,
+    next_level: Option<String>,
+}
+
+#[derive(Component)]
+struct CompletionResults;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum CompletionButton {
+    Retry,
+    NextLevel,
+}
+
+/// `minutes:seconds.hundredths`
+fn format_time(seconds: f32) -> String {
+    let hundredths = (seconds * 100.0).round() as u32;
+    format!(
+        "{}:{:02}.{:02}",
+        hundredths / 6000,
+        hundredths / 100 % 60,
+        hundredths % 100
+    )
+}
+
+fn create_run_timer_text(mut cmd: Commands) {
+    cmd.spawn((
+        Name::new("Run Timer"),
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: 32.,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(16.),
+            right: Val::Px(24.),
+            ..default()
+        }),
+        RunTimerText,
+        PersistentUi,
+    ));
+}
+
+fn create_completion_screen(mut cmd: Commands) {
+    let button = |parent: &mut ChildBuilder, label: &str, action: CompletionButton| {
+        parent
+            .spawn((
+                Name::new(format!("{label} Button")),
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(200.),
+                        height: Val::Px(50.),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..default()
+                },
+                action,
+                PersistentUi,
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    TextBundle::from_section(
+                        label,
+                        TextStyle {
+                            font_size: 32.,
+                            ..default()
+                        },
+                    ),
+                    PersistentUi,
+                ));
+            });
+    };
+
+    cmd.spawn((
+        Name::new("Completion Screen"),
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(24.),
+                ..default()
+            },
+            background_color: Color::BLACK.with_a(0.6).into(),
+            visibility: Visibility::Hidden,
+            // above the touch controls, below the screen fade
+            z_index: ZIndex::Global(i32::MAX - 1),
+            ..default()
+        },
+        CompletionScreen::default(),
+        PersistentUi,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TextBundle::from_section(
+                "Level Complete",
+                TextStyle {
+                    font_size: 64.,
+                    ..default()
+                },
+            ),
+            PersistentUi,
+        ));
+        parent.spawn((
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: 32.,
+                    ..default()
+                },
+            )
+            .with_text_alignment(TextAlignment::Center),
+            CompletionResults,
+            PersistentUi,
+        ));
+        parent
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        column_gap: Val::Px(24.),
+                        ..default()
+                    },
+                    ..default()
+                },
+                PersistentUi,
+            ))
+            .with_children(|parent| {
+                button(parent, "Retry", CompletionButton::Retry);
+                button(parent, "Next Level", CompletionButton::NextLevel);
+            });
+    });
+}
+
+fn show_run_timer(run: Res<RunTimer>, mut text_q: Query<&mut Text, With<RunTimerText>>) {
+    if !run.is_changed() {
+        return;
+    }
+    let label = match run.state() {
+        RunState::Waiting => String::new(),
+        RunState::Running | RunState::Finished => format_time(run.elapsed_seconds()),
+    };
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = label.clone();
+    }
+}
+
+fn show_completion_screen(
+    mut completed_reader: EventReader<LevelCompleted>,
+    mut screen_q: Query<(&mut CompletionScreen, &mut Visibility)>,
+    mut results_q: Query<&mut Text, With<CompletionResults>>,
+    mut button_q: Query<(&CompletionButton, &mut Style)>,
+    mut cam_q: Query<&mut ThirdPersonCamera>,
+) {
+    let Some(completed) = completed_reader.iter().last() else {
+        return;
+    };
+
+    for (mut screen, mut visibility) in screen_q.iter_mut() {
+        screen.level = completed.level.clone();
+        screen.next_level = completed.next_level.clone();
+        *visibility = Visibility::Inherited;
+    }
+    for mut text in results_q.iter_mut() {
+        text.sections[0].value = format!(
+            "Time {}\nDeaths {}\nBest {}{}",
+            format_time(completed.time),
+            completed.deaths,
+            format_time(completed.best_time),
+            if completed.new_best {
+                "  New best!"
+            } else {
+                ""
+            },
+        );
+    }
+    for (button, mut style) in button_q.iter_mut() {
+        if *button == CompletionButton::NextLevel {
+            style.display = if completed.next_level.is_some() {
+                Display::Flex
+            } else {
+                Display::None
+            };
+        }
+    }
+    // free the cursor so the buttons can be clicked
+    for mut cam in cam_q.iter_mut() {
+        cam.cursor_lock_active = false;
+    }
+}
+
+fn hide_completion_screen_on_level_spawned(
+    mut spawned_reader: EventReader<LevelSpawned>,
+    mut screen_q: Query<&mut Visibility, With<CompletionScreen>>,
+) {
+    if spawned_reader.iter().any(|spawned| spawned.first_load) {
+        for mut visibility in screen_q.iter_mut() {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn press_completion_buttons(
+    mut button_q: Query<
+        (&CompletionButton, &Interaction, &mut BackgroundColor),
+        Changed<Interaction>,
+    >,
+    screen_q: Query<&CompletionScreen>,
+    mut cam_q: Query<&mut ThirdPersonCamera>,
+    mut load_writer: EventWriter<LoadLevel>,
+) {
+    let Ok(screen) = screen_q.get_single() else {
+        return;
+    };
+
+    for (button, interaction, mut background) in button_q.iter_mut() {
+        background.0 = match interaction {
+            Interaction::Hovered => BUTTON_HOVERED_COLOR,
+            Interaction::Pressed | Interaction::None => BUTTON_COLOR,
+        };
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let path = match button {
+            CompletionButton::Retry => Some(&screen.level),
+            CompletionButton::NextLevel => screen.next_level.as_ref(),
+        };
+        if let Some(path) = path {
+            load_writer.send(LoadLevel { path: path.clone() });
+            for mut cam in cam_q.iter_mut() {
+                cam.cursor_lock_active = true;
+            }
+        }
+    }
+}
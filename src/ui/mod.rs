@@ -1,14 +1,31 @@
+mod completion;
+
+use crate::actions::{Action, ActionButton};
+use crate::player::{Dash, DashState, Player};
 use crate::virtual_joystick::*;
 use crate::JoystickControllerID;
 use bevy::prelude::*;
 use bevy::ui::FocusPolicy;
+use completion::CompletionPlugin;
+
+pub use completion::{CompletionScreen, RunTimerText};
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, create_ui)
-            .add_systems(Update, (toggle_ui_on_tab, show_ui_on_any_touch));
+        app.add_plugins(CompletionPlugin)
+            .init_resource::<ScreenFade>()
+            .add_systems(Startup, (create_ui, create_screen_fade))
+            .add_systems(
+                Update,
+                (
+                    toggle_ui_on_tab,
+                    show_ui_on_any_touch,
+                    show_dash_cooldown,
+                    update_screen_fade,
+                ),
+            );
     }
 }
 
@@ -18,6 +35,41 @@ pub struct JumpButton;
 #[derive(Component)]
 pub struct DashButton;
 
+/// UI node that Tab and touches don't show or hide along with the touch controls
+#[derive(Component)]
+pub struct PersistentUi;
+
+/// Full screen overlay faded by [`ScreenFade`]
+#[derive(Component)]
+pub struct ScreenFadeOverlay;
+
+/// Fades the screen to and from black
+#[derive(Resource)]
+pub struct ScreenFade {
+    /// Opacity the overlay moves towards
+    pub target: f32,
+    /// Seconds a full fade takes
+    pub duration: f32,
+    alpha: f32,
+}
+
+impl Default for ScreenFade {
+    fn default() -> Self {
+        Self {
+            target: 0.0,
+            duration: 0.3,
+            alpha: 0.0,
+        }
+    }
+}
+
+impl ScreenFade {
+    /// Current opacity of the overlay
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+}
+
 fn create_ui(mut cmd: Commands, asset_server: Res<AssetServer>) {
     cmd.spawn((
         Name::new("Virtual Joystick"),
@@ -61,6 +113,7 @@ fn create_ui(mut cmd: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         JumpButton,
+        ActionButton(Action::Jump),
     ))
     .remove::<Button>()
     .with_children(|parent| {
@@ -97,6 +150,7 @@ fn create_ui(mut cmd: Commands, asset_server: Res<AssetServer>) {
             ..default()
         },
         DashButton,
+        ActionButton(Action::Dash),
     ))
     .remove::<Button>()
     .with_children(|parent| {
@@ -117,10 +171,52 @@ fn create_ui(mut cmd: Commands, asset_server: Res<AssetServer>) {
     });
 }
 
+fn create_screen_fade(mut cmd: Commands) {
+    cmd.spawn((
+        Name::new("Screen Fade"),
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.),
+                height: Val::Percent(100.),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            background_color: Color::NONE.into(),
+            focus_policy: FocusPolicy::Pass,
+            z_index: ZIndex::Global(i32::MAX),
+            ..default()
+        },
+        ScreenFadeOverlay,
+        PersistentUi,
+    ));
+}
+
+fn update_screen_fade(
+    mut fade: ResMut<ScreenFade>,
+    mut overlay_q: Query<&mut BackgroundColor, With<ScreenFadeOverlay>>,
+    time: Res<Time>,
+) {
+    let step = time.delta_seconds() / fade.duration.max(f32::EPSILON);
+    let alpha = if fade.alpha < fade.target {
+        (fade.alpha + step).min(fade.target)
+    } else {
+        (fade.alpha - step).max(fade.target)
+    };
+    if alpha == fade.alpha {
+        return;
+    }
+    fade.alpha = alpha;
+
+    for mut background in overlay_q.iter_mut() {
+        background.0 = Color::BLACK.with_a(alpha);
+    }
+}
+
+#[allow(clippy::type_complexity)]
 fn toggle_ui_on_tab(
     mut commands: Commands,
     input: Res<Input<KeyCode>>,
-    mut node_q: Query<(Entity, &mut Visibility), With<Node>>,
+    mut node_q: Query<(Entity, &mut Visibility), (With<Node>, Without<PersistentUi>)>,
     mut joystick_q: Query<&mut VirtualJoystickNode<JoystickControllerID>>,
     button_q: Query<&Button>,
     control_button_q: Query<Or<(With<JumpButton>, With<DashButton>)>>,
@@ -148,10 +244,11 @@ fn toggle_ui_on_tab(
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn show_ui_on_any_touch(
     mut commands: Commands,
     touches: Res<Touches>,
-    mut node_q: Query<(Entity, &mut Visibility), With<Node>>,
+    mut node_q: Query<(Entity, &mut Visibility), (With<Node>, Without<PersistentUi>)>,
     mut joystick_q: Query<&mut VirtualJoystickNode<JoystickControllerID>>,
     control_button_q: Query<Or<(With<JumpButton>, With<DashButton>)>>,
 ) {
@@ -169,3 +266,35 @@ fn show_ui_on_any_touch(
         }
     }
 }
+
+fn show_dash_cooldown(
+    player_q: Query<(&Dash, &DashState), With<Player>>,
+    dash_button_q: Query<&Children, With<DashButton>>,
+    mut text_q: Query<&mut Text>,
+) {
+    let Ok((dash, dash_state)) = player_q.get_single() else {
+        return;
+    };
+
+    let cooldown = dash_state.cooldown_remaining();
+    let (label, color) = if cooldown > 0.0 {
+        (format!("{cooldown:.1}"), Color::GRAY)
+    } else if dash_state.charges() == 0 {
+        ("Dash".to_string(), Color::GRAY)
+    } else if dash.max_charges > 1 {
+        (format!("Dash x{}", dash_state.charges()), Color::WHITE)
+    } else {
+        ("Dash".to_string(), Color::WHITE)
+    };
+
+    for children in dash_button_q.iter() {
+        for child in children.iter() {
+            if let Ok(mut text) = text_q.get_mut(*child) {
+                if text.sections[0].value != label {
+                    text.sections[0].value = label.clone();
+                }
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
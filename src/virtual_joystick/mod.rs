@@ -19,6 +19,10 @@ pub use joystick::{
 
 use joystick::{extract_joystick_node, VirtualJoystickKnob};
 
+/// The systems that send [`VirtualJoystickEvent`]s in [`PreUpdate`]
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VirtualJoystickSet;
+
 #[derive(Default)]
 pub struct VirtualJoystickPlugin<S> {
     _marker: PhantomData<S>,
@@ -45,7 +49,7 @@ impl<S: Hash + Sync + Send + Clone + Default + Reflect + TypePath + FromReflect
                 PreUpdate,
                 update_joystick_by_mouse.before(update_input::<S>), // .run_if(run_if_pc),
             )
-            .add_systems(PreUpdate, update_input::<S>)
+            .add_systems(PreUpdate, update_input::<S>.in_set(VirtualJoystickSet))
             .add_systems(
                 PostUpdate,
                 joystick_image_node_system::<S>.before(UiSystem::Layout),